@@ -12,7 +12,7 @@ use axum_helpers::{
     app::AxumApp,
     auth::{
         AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
-        LoginInfoExtractor, RefreshToken,
+        LoginInfoExtractor, RefreshToken, RenewalDecision,
     },
 };
 use clap::Parser;
@@ -98,8 +98,8 @@ impl AuthHandler<LoginInfo> for AppState {
         &mut self,
         access_token: &AccessToken,
         _login_info: &Arc<LoginInfo>,
-    ) -> Option<(AccessToken, Duration)> {
-        Some((access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION))
+    ) -> RenewalDecision {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
     }
 
     async fn revoke_access_token(
@@ -261,7 +261,7 @@ async fn api_login(
 ) -> Result<(StatusCode, AccessTokenResponse, Json<LoginResponse>), StatusCode> {
     let (access_token_response, _login_info) = state
         .login(&login_request.loginname, login_request.password)
-        .ok_or_else(|| StatusCode::BAD_REQUEST)?;
+        .ok_or(StatusCode::BAD_REQUEST)?;
 
     log::info!("User logged in, loginname = '{}'", login_request.loginname);
 
@@ -285,12 +285,7 @@ async fn api_get_logged_in_users(
     _login_info: LoginInfoExtractor<LoginInfo>,
     state: State<AppState>,
 ) -> Json<serde_json::Value> {
-    let login_infos = state
-        .logins
-        .lock()
-        .iter()
-        .map(|(_access_token, login_info)| login_info.clone())
-        .collect::<Vec<_>>();
+    let login_infos = state.logins.lock().values().cloned().collect::<Vec<_>>();
 
     Json(json!({
         "login_infos": login_infos
@@ -1,81 +1,784 @@
-use std::net::SocketAddr;
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, OnceLock,
+    },
+    time::Duration,
+};
 
-use axum::Router;
-use tokio::{sync::watch, task::JoinHandle};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    handler::Handler,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::IntoResponse,
+    routing::get,
+    BoxError, Router,
+};
+use hyper::{body::Incoming, service::service_fn};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+};
+use tokio::{
+    sync::watch,
+    task::{JoinHandle, JoinSet},
+};
+use tower::Service;
+use tower_http::compression::CompressionLayer;
+
+use crate::auth::AuthHandler;
 
 #[derive(Debug)]
 pub enum RunServerError {
     TcpBind(std::io::Error),
+    /// An auth handler registered with `AxumApp::with_auth_validation` is not ready.
+    AuthNotReady(BoxError),
 }
 
+type RouterFactory = Arc<dyn Fn() -> Router + Send + Sync>;
+type RouterFinalizer = Arc<dyn Fn(Router) -> Router + Send + Sync>;
+type AuthValidation =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<(), BoxError>> + Send>> + Send + Sync>;
+
 pub struct AxumApp {
     router: Router,
+    router_factory: Option<RouterFactory>,
+    fallback: Option<RouterFinalizer>,
+    method_not_allowed_fallback: Option<RouterFinalizer>,
+    drain_deadline: Option<Duration>,
+    compression: bool,
+    inflight_gauge: Option<Arc<AtomicUsize>>,
+    connect_info: bool,
+    drain_503: bool,
+    reuse_port: bool,
+    // built once and shared by the servers of the app, reset whenever the router or a router
+    // transformation of the app changes
+    built_router: OnceLock<Router>,
+    auth_validations: Vec<AuthValidation>,
 
     should_run_sender: watch::Sender<bool>,
+    shutdown_complete_sender: watch::Sender<bool>,
     joinhandles: Vec<JoinHandle<()>>,
 }
 
 impl AxumApp {
     pub fn new(router: Router) -> Self {
         let (should_run_sender, _receiver) = watch::channel(true);
+        let (shutdown_complete_sender, _receiver) = watch::channel(false);
         Self {
             router,
+            router_factory: None,
+            fallback: None,
+            method_not_allowed_fallback: None,
+            drain_deadline: None,
+            compression: false,
+            inflight_gauge: None,
+            connect_info: false,
+            drain_503: false,
+            reuse_port: false,
+            built_router: OnceLock::new(),
+            auth_validations: Vec::new(),
 
             should_run_sender,
+            shutdown_complete_sender,
             joinhandles: Vec::new(),
         }
     }
 
+    /// Starts an `AxumAppBuilder` to configure the app serving `router` fluently, `new` is the
+    /// shortcut for an app without options.
+    pub fn builder(router: Router) -> AxumAppBuilder {
+        AxumAppBuilder {
+            app: Self::new(router),
+        }
+    }
+
+    /// Creates an app whose router is built by `router_factory` every time a server is spawned,
+    /// so every server gets its own state.
+    /// The stored router (see `router`, `router_mut` and `map_router`) is empty in this case,
+    /// it is merged into every router built by the factory.
+    pub fn new_with(router_factory: impl Fn() -> Router + Send + Sync + 'static) -> Self {
+        let mut app = Self::new(Router::new());
+        app.router_factory = Some(Arc::new(router_factory));
+        app
+    }
+
+    /// Mounts a health-check route that responds with 200 and `ok`.
+    /// The route is added outside of the layers of the router, so e.g., it does not require authentication.
+    pub fn with_health_route(mut self, path: &str) -> Self {
+        self.map_router(|router| router.route(path, get(get_health)));
+        self
+    }
+
+    /// Registers a handler for the requests that do not match any route.
+    /// It is installed on the router when a server is spawned.
+    pub fn with_fallback<HandlerType, T>(mut self, handler: HandlerType) -> Self
+    where
+        HandlerType: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.fallback = Some(Arc::new(move |router: Router| {
+            router.fallback(handler.clone())
+        }));
+        self.built_router.take();
+        self
+    }
+
+    /// Registers a handler for the requests that match a route, but not its methods.
+    /// It is installed on the router when a server is spawned.
+    pub fn with_method_not_allowed_fallback<HandlerType, T>(mut self, handler: HandlerType) -> Self
+    where
+        HandlerType: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.method_not_allowed_fallback = Some(Arc::new(move |router: Router| {
+            router.method_not_allowed_fallback(handler.clone())
+        }));
+        self.built_router.take();
+        self
+    }
+
+    /// Responds with 404 and `{"error":"not_found"}` to the requests that do not match any route,
+    /// and with 405 and `{"error":"method_not_allowed"}` to the requests that match a route, but not its methods.
+    pub fn with_json_not_found(self) -> Self {
+        self.with_fallback(json_not_found)
+            .with_method_not_allowed_fallback(json_method_not_allowed)
+    }
+
+    /// Compresses the responses with gzip or deflate, negotiated through the `Accept-Encoding`
+    /// header of the request. Off by default, e.g., a reverse proxy may already compress the responses.
+    pub fn with_compression(mut self) -> Self {
+        self.compression = true;
+        self.built_router.take();
+        self
+    }
+
+    /// Counts the requests in flight through the servers of the app, see `inflight`.
+    pub fn with_inflight_gauge(mut self) -> Self {
+        self.inflight_gauge = Some(Arc::new(AtomicUsize::new(0)));
+        self.built_router.take();
+        self
+    }
+
+    /// Number of requests currently in flight, always 0 unless `with_inflight_gauge` is set.
+    pub fn inflight(&self) -> usize {
+        self.inflight_gauge
+            .as_ref()
+            .map(|inflight_gauge| inflight_gauge.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
+
+    /// Serves the router with `into_make_service_with_connect_info::<SocketAddr>`, so request
+    /// handlers can extract the peer address with `ConnectInfo<SocketAddr>`.
+    /// The test server listens on a random local port in this case, as connect info requires a
+    /// real connection.
+    pub fn with_connect_info(mut self) -> Self {
+        self.connect_info = true;
+        self
+    }
+
+    /// Binds the listeners with `SO_REUSEADDR` and, on the Unix platforms supporting it,
+    /// `SO_REUSEPORT`, so several servers, e.g., the old and the new process of a zero-downtime
+    /// restart, can listen on the same port, the kernel distributes the connections among them.
+    /// Off by default.
+    pub fn with_reuse_port(mut self, reuse_port: bool) -> Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
+    /// Responds with `503 Service Unavailable` and `Connection: close` to the requests received
+    /// after `stop_server` was called, e.g., on connections kept alive while the servers drain,
+    /// so a load balancer reroutes them. Requests already in flight are completed as usual.
+    /// The servers stopped by the shutdown future of `spawn_server_with_shutdown` are not
+    /// affected.
+    pub fn with_drain_503(mut self) -> Self {
+        self.drain_503 = true;
+        self.built_router.take();
+        self
+    }
+
+    /// Calls `AuthHandler::ready` of `auth_handler` before a server is bound, the server is not
+    /// spawned and `RunServerError::AuthNotReady` is returned if it fails, e.g., when the token
+    /// store is unreachable. The test server does not validate the auth handlers.
+    pub fn with_auth_validation<LoginInfoType, AuthHandlerType>(
+        mut self,
+        auth_handler: AuthHandlerType,
+    ) -> Self
+    where
+        LoginInfoType: Send + Sync,
+        AuthHandlerType: AuthHandler<LoginInfoType>,
+    {
+        self.auth_validations.push(Arc::new(move || {
+            let auth_handler = auth_handler.clone();
+            Box::pin(async move { auth_handler.ready().await })
+        }));
+        self
+    }
+
+    pub fn router(&self) -> &Router {
+        &self.router
+    }
+
+    pub fn router_mut(&mut self) -> &mut Router {
+        self.built_router.take();
+        &mut self.router
+    }
+
+    /// Transforms the stored router, e.g., to apply a layer to every route of the app.
+    /// Servers spawned afterwards serve the transformed router.
+    pub fn map_router(&mut self, f: impl FnOnce(Router) -> Router) {
+        let router = std::mem::take(&mut self.router);
+        self.router = f(router);
+        self.built_router.take();
+    }
+
+    /// Sets how long in-flight requests are waited for after the server is stopped.
+    /// When the deadline elapses the remaining connections are closed forcibly.
+    /// `None` (the default) waits for the in-flight requests indefinitely.
+    pub fn set_drain_deadline(&mut self, drain_deadline: Option<Duration>) {
+        self.drain_deadline = drain_deadline;
+    }
+
+    pub fn drain_deadline(&self) -> Option<Duration> {
+        self.drain_deadline
+    }
+
+    /// Subscribes to the completion of the shutdown, the value turns `true` once `join` has
+    /// joined every server, i.e., the servers have finished draining, e.g., to close a database
+    /// pool afterwards.
+    pub fn subscribe_shutdown(&self) -> watch::Receiver<bool> {
+        self.shutdown_complete_sender.subscribe()
+    }
+
     pub fn stop_server(&self) {
         let _ = self.should_run_sender.send(false);
     }
 
-    #[cfg(test)]
+    /// Makes a stopped app servable again, i.e., servers spawned after `restart` run until
+    /// `stop_server` is called again, and the shutdown completion reported by
+    /// `subscribe_shutdown` is reset to `false`.
+    ///
+    /// Once `stop_server` was called every server spawned afterwards stops immediately, until
+    /// `restart` is called. Restart only after `join` returned, servers that are still draining
+    /// are not affected by `restart`, but `join` would wait for them together with the new ones.
+    pub fn restart(&mut self) {
+        if !self.joinhandles.is_empty() {
+            log::warn!("Restarting the app before the previous servers were joined");
+        }
+
+        self.should_run_sender.send_replace(true);
+        self.shutdown_complete_sender.send_replace(false);
+    }
+
+    /// Serves the same router as `spawn_server`, so the test server exercises the routes and
+    /// fallbacks configured on the app as well.
+    #[cfg(any(test, feature = "testing"))]
     pub fn spawn_test_server(&self) -> Result<axum_test::TestServer, Box<dyn ::std::error::Error>> {
         use axum_test::TestServer;
 
-        let router = self.router.clone();
+        let router = self.shared_router();
 
-        Ok(TestServer::new(router.into_make_service())?)
+        if self.connect_info {
+            Ok(TestServer::new(
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )?)
+        } else {
+            Ok(TestServer::new(router.into_make_service())?)
+        }
     }
 
     pub async fn spawn_server(
         &mut self,
         listener_address: SocketAddr,
     ) -> Result<(), RunServerError> {
-        let router = self.router.clone();
+        self.spawn_server_with_shutdown(listener_address, std::future::pending())
+            .await
+    }
+
+    /// Same as `spawn_server`, but the server is also stopped when `shutdown` completes.
+    /// `stop_server` stops the server as well, whichever happens first.
+    pub async fn spawn_server_with_shutdown(
+        &mut self,
+        listener_address: SocketAddr,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), RunServerError> {
+        let (_local_address, server) = self.bind_server(listener_address, shutdown).await?;
+        self.joinhandles.push(tokio::spawn(server));
 
-        let mut should_run_receiver = self.should_run_sender.subscribe();
+        Ok(())
+    }
 
-        log::info!("listening on {}", listener_address);
-        let listener = tokio::net::TcpListener::bind(listener_address)
-            .await
-            .map_err(RunServerError::TcpBind)?;
+    /// Same as `spawn_server`, but it also returns the address the server is listening on, e.g.,
+    /// when port 0 was requested, and the handle of the server task, e.g., to detect a server
+    /// task that ended unexpectedly. `join` still waits for the server.
+    pub async fn spawn_server_handle(
+        &mut self,
+        listener_address: SocketAddr,
+    ) -> Result<(SocketAddr, JoinHandle<()>), RunServerError> {
+        let (local_address, server) = self
+            .bind_server(listener_address, std::future::pending())
+            .await?;
 
+        // a JoinHandle can be awaited only once, so `join` waits for the sender to be dropped,
+        // which happens when the server task ends, even if it panics
+        let (server_task_alive_sender, server_task_alive_receiver) =
+            tokio::sync::oneshot::channel::<()>();
         let joinhandle = tokio::spawn(async move {
-            let _ = axum::serve(listener, router.into_make_service())
-                .with_graceful_shutdown(async move {
-                    while should_run_receiver.changed().await.is_ok() {
-                        if !*should_run_receiver.borrow() {
-                            break;
-                        }
-                    }
-                })
-                .await
-                .inspect_err(|e| log::warn!("Server error = {e}"));
+            let _server_task_alive_sender = server_task_alive_sender;
+            server.await;
         });
+        self.joinhandles.push(tokio::spawn(async move {
+            let _ = server_task_alive_receiver.await;
+        }));
 
-        self.joinhandles.push(joinhandle);
+        Ok((local_address, joinhandle))
+    }
+
+    /// Serves on the current task until `stop_server` is called, instead of spawning the server
+    /// like `spawn_server`, so there is nothing to `join`.
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use axum::{routing::get, Router};
+    /// use axum_helpers::app::AxumApp;
+    ///
+    /// let app = AxumApp::new(Router::new().route("/", get(|| async { "index" })));
+    /// app.run_server("127.0.0.1:8080".parse().unwrap())
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn run_server(&self, listener_address: SocketAddr) -> Result<(), RunServerError> {
+        let (_local_address, server) = self
+            .bind_server(listener_address, std::future::pending())
+            .await?;
+        server.await;
 
         Ok(())
     }
 
+    /// Binds the listener and returns its local address and the future serving on it until the
+    /// server is stopped.
+    async fn bind_server(
+        &self,
+        listener_address: SocketAddr,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(SocketAddr, impl Future<Output = ()> + Send + 'static), RunServerError> {
+        for auth_validation in &self.auth_validations {
+            auth_validation()
+                .await
+                .map_err(RunServerError::AuthNotReady)?;
+        }
+
+        let in_flight_request_count = Arc::new(AtomicUsize::new(0));
+        let router = if self.drain_deadline.is_some() {
+            // the router is wrapped instead of layered, layering it would rebuild every route of
+            // the shared router for each server
+            count_in_flight_requests(
+                Router::new().fallback_service(self.shared_router()),
+                in_flight_request_count.clone(),
+            )
+        } else {
+            self.shared_router()
+        };
+
+        let should_run_receiver = self.should_run_sender.subscribe();
+        let drain_deadline = self.drain_deadline;
+        let connect_info = self.connect_info;
+        let reuse_port = self.reuse_port;
+
+        log::info!("listening on {}", listener_address);
+        let listener = if reuse_port {
+            bind_reusable(listener_address)
+        } else {
+            tokio::net::TcpListener::bind(listener_address).await
+        }
+        .map_err(RunServerError::TcpBind)?;
+        let local_address = listener.local_addr().map_err(RunServerError::TcpBind)?;
+
+        Ok((local_address, async move {
+            let shutdown_signal = async move {
+                tokio::select! {
+                    _ = wait_for_stop_signal(should_run_receiver) => {}
+                    _ = shutdown => {}
+                }
+            };
+
+            serve(
+                listener,
+                router,
+                connect_info,
+                shutdown_signal,
+                drain_deadline,
+                in_flight_request_count,
+            )
+            .await;
+        }))
+    }
+
+    /// Router served by the servers of the app. Cloning a `Router` only clones an `Arc`, so the
+    /// servers share the routes of the router built once, instead of rebuilding them for each
+    /// listener. The router of a router factory is built for every server, see `new_with`.
+    fn shared_router(&self) -> Router {
+        if self.router_factory.is_some() {
+            self.build_router()
+        } else {
+            self.built_router
+                .get_or_init(|| self.build_router())
+                .clone()
+        }
+    }
+
+    /// Builds the router served by both `spawn_server` and `spawn_test_server`,
+    /// every router transformation of the app belongs here to keep them from diverging.
+    fn build_router(&self) -> Router {
+        let router = if let Some(router_factory) = &self.router_factory {
+            router_factory().merge(self.router.clone())
+        } else {
+            self.router.clone()
+        };
+
+        let router = if let Some(method_not_allowed_fallback) = &self.method_not_allowed_fallback {
+            method_not_allowed_fallback(router)
+        } else {
+            router
+        };
+
+        let router = if let Some(fallback) = &self.fallback {
+            fallback(router)
+        } else {
+            router
+        };
+
+        let router = if self.compression {
+            router.layer(CompressionLayer::new())
+        } else {
+            router
+        };
+
+        let router = if let Some(inflight_gauge) = &self.inflight_gauge {
+            count_in_flight_requests(router, inflight_gauge.clone())
+        } else {
+            router
+        };
+
+        if self.drain_503 {
+            reject_while_draining(router, self.should_run_sender.subscribe())
+        } else {
+            router
+        }
+    }
+
     pub async fn join(&mut self) {
         for joinhandle in self.joinhandles.drain(..) {
             let _ = joinhandle
                 .await
                 .inspect_err(|e| log::warn!("Could not join server task, error = {e}"));
         }
+
+        self.shutdown_complete_sender.send_replace(true);
+    }
+}
+
+/// Configures an `AxumApp` in one place before it is built, see `AxumApp::builder`.
+/// Every option is disabled by default, as with `AxumApp::new`.
+pub struct AxumAppBuilder {
+    app: AxumApp,
+}
+
+impl AxumAppBuilder {
+    /// See `AxumApp::with_health_route`.
+    pub fn health_route(mut self, path: &str) -> Self {
+        self.app = self.app.with_health_route(path);
+        self
+    }
+
+    /// See `AxumApp::with_fallback`.
+    pub fn fallback<HandlerType, T>(mut self, handler: HandlerType) -> Self
+    where
+        HandlerType: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.app = self.app.with_fallback(handler);
+        self
+    }
+
+    /// See `AxumApp::with_method_not_allowed_fallback`.
+    pub fn method_not_allowed_fallback<HandlerType, T>(mut self, handler: HandlerType) -> Self
+    where
+        HandlerType: Handler<T, ()> + Sync,
+        T: 'static,
+    {
+        self.app = self.app.with_method_not_allowed_fallback(handler);
+        self
+    }
+
+    /// See `AxumApp::with_json_not_found`.
+    pub fn json_not_found(mut self) -> Self {
+        self.app = self.app.with_json_not_found();
+        self
+    }
+
+    /// See `AxumApp::with_compression`.
+    pub fn compression(mut self) -> Self {
+        self.app = self.app.with_compression();
+        self
+    }
+
+    /// See `AxumApp::with_inflight_gauge`.
+    pub fn inflight_gauge(mut self) -> Self {
+        self.app = self.app.with_inflight_gauge();
+        self
+    }
+
+    /// See `AxumApp::with_connect_info`.
+    pub fn connect_info(mut self) -> Self {
+        self.app = self.app.with_connect_info();
+        self
+    }
+
+    /// See `AxumApp::with_reuse_port`.
+    pub fn reuse_port(mut self, reuse_port: bool) -> Self {
+        self.app = self.app.with_reuse_port(reuse_port);
+        self
+    }
+
+    /// See `AxumApp::with_drain_503`.
+    pub fn drain_503(mut self) -> Self {
+        self.app = self.app.with_drain_503();
+        self
+    }
+
+    /// See `AxumApp::set_drain_deadline`.
+    pub fn drain_deadline(mut self, drain_deadline: Duration) -> Self {
+        self.app.set_drain_deadline(Some(drain_deadline));
+        self
+    }
+
+    /// See `AxumApp::with_auth_validation`.
+    pub fn auth_validation<LoginInfoType, AuthHandlerType>(
+        mut self,
+        auth_handler: AuthHandlerType,
+    ) -> Self
+    where
+        LoginInfoType: Send + Sync,
+        AuthHandlerType: AuthHandler<LoginInfoType>,
+    {
+        self.app = self.app.with_auth_validation(auth_handler);
+        self
+    }
+
+    pub fn build(self) -> AxumApp {
+        self.app
+    }
+}
+
+/// Binds a listener with `SO_REUSEADDR` and `SO_REUSEPORT` where the latter is supported, with
+/// the same backlog as `TcpListener::bind`.
+fn bind_reusable(listener_address: SocketAddr) -> std::io::Result<tokio::net::TcpListener> {
+    let socket = if listener_address.is_ipv4() {
+        tokio::net::TcpSocket::new_v4()?
+    } else {
+        tokio::net::TcpSocket::new_v6()?
+    };
+    socket.set_reuseaddr(true)?;
+    #[cfg(all(
+        unix,
+        not(target_os = "solaris"),
+        not(target_os = "illumos"),
+        not(target_os = "cygwin")
+    ))]
+    socket.set_reuseport(true)?;
+    socket.bind(listener_address)?;
+    socket.listen(1024)
+}
+
+async fn get_health() -> &'static str {
+    "ok"
+}
+
+async fn json_not_found() -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        [(header::CONTENT_TYPE, "application/json")],
+        r#"{"error":"not_found"}"#,
+    )
+}
+
+async fn json_method_not_allowed() -> impl IntoResponse {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        [(header::CONTENT_TYPE, "application/json")],
+        r#"{"error":"method_not_allowed"}"#,
+    )
+}
+
+/// Serves `router` on the connections accepted by `listener` until `shutdown_signal` completes,
+/// then waits for the in-flight requests of the connections. The connections are served by tasks
+/// of a join set instead of detached tasks, so the connections still open when `drain_deadline`
+/// elapses are closed by aborting their tasks.
+async fn serve(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    connect_info: bool,
+    shutdown_signal: impl Future<Output = ()>,
+    drain_deadline: Option<Duration>,
+    in_flight_request_count: Arc<AtomicUsize>,
+) {
+    let mut connections = JoinSet::new();
+    let (graceful_shutdown_sender, graceful_shutdown_receiver) = watch::channel(false);
+    tokio::pin!(shutdown_signal);
+
+    loop {
+        let (tcp_stream, remote_address) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    if !is_connection_error(&e) {
+                        // e.g., out of file descriptors, retrying right away would spin
+                        log::warn!("Could not accept connection, error = {e}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    continue;
+                }
+            },
+            // the tasks of the closed connections are reaped, so the join set does not grow
+            Some(_) = connections.join_next() => continue,
+            _ = &mut shutdown_signal => break,
+        };
+
+        let router = router.clone();
+        let service = service_fn(move |req: Request<Incoming>| {
+            let mut req = req.map(Body::new);
+            if connect_info {
+                req.extensions_mut().insert(ConnectInfo(remote_address));
+            }
+            router.clone().call(req)
+        });
+        let mut graceful_shutdown_receiver = graceful_shutdown_receiver.clone();
+        let graceful_shutdown = async move {
+            let _ = graceful_shutdown_receiver
+                .wait_for(|graceful_shutdown| *graceful_shutdown)
+                .await;
+        };
+        connections.spawn(async move {
+            let builder = Builder::new(TokioExecutor::new());
+            let connection =
+                builder.serve_connection_with_upgrades(TokioIo::new(tcp_stream), service);
+            tokio::pin!(connection);
+
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = graceful_shutdown => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            let _ = result.inspect_err(|e| log::debug!("Connection error = {e}"));
+        });
+    }
+
+    drop(listener);
+    let _ = graceful_shutdown_sender.send(true);
+
+    if let Some(drain_deadline) = drain_deadline {
+        if tokio::time::timeout(drain_deadline, join_connections(&mut connections))
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "Drain deadline elapsed, closing {} connection(s) with {} in-flight request(s)",
+                connections.len(),
+                in_flight_request_count.load(Ordering::SeqCst)
+            );
+            connections.shutdown().await;
+        }
+    } else {
+        join_connections(&mut connections).await;
+    }
+}
+
+async fn join_connections(connections: &mut JoinSet<()>) {
+    while connections.join_next().await.is_some() {}
+}
+
+/// Errors of a single connection that was closed before it could be accepted.
+fn is_connection_error(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionReset
+    )
+}
+
+async fn wait_for_stop_signal(mut should_run_receiver: watch::Receiver<bool>) {
+    // the app may have been stopped before the server was spawned
+    if !*should_run_receiver.borrow_and_update() {
+        return;
+    }
+
+    while should_run_receiver.changed().await.is_ok() {
+        if !*should_run_receiver.borrow() {
+            break;
+        }
+    }
+}
+
+/// Counts the requests in flight, the count is decremented even if the request handler panics
+/// or the request is dropped.
+fn count_in_flight_requests(router: Router, in_flight_request_count: Arc<AtomicUsize>) -> Router {
+    router.layer(axum::middleware::from_fn(
+        move |req: Request, next: Next| {
+            let in_flight_request_guard =
+                InFlightRequestGuard::new(in_flight_request_count.clone());
+            async move {
+                let response = next.run(req).await;
+                drop(in_flight_request_guard);
+                response
+            }
+        },
+    ))
+}
+
+/// Responds with 503 and `Connection: close` once the app is stopped, the value of
+/// `should_run_receiver` is checked for every request, so a restarted app serves again.
+fn reject_while_draining(router: Router, should_run_receiver: watch::Receiver<bool>) -> Router {
+    router.layer(axum::middleware::from_fn(
+        move |req: Request, next: Next| {
+            let should_run = *should_run_receiver.borrow();
+            async move {
+                if should_run {
+                    next.run(req).await
+                } else {
+                    (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        [(header::CONNECTION, "close")],
+                    )
+                        .into_response()
+                }
+            }
+        },
+    ))
+}
+
+struct InFlightRequestGuard(Arc<AtomicUsize>);
+
+impl InFlightRequestGuard {
+    fn new(in_flight_request_count: Arc<AtomicUsize>) -> Self {
+        in_flight_request_count.fetch_add(1, Ordering::SeqCst);
+        Self(in_flight_request_count)
+    }
+}
+
+impl Drop for InFlightRequestGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
@@ -0,0 +1,63 @@
+use std::convert::Infallible;
+
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use axum_extra::extract::cookie::SameSite;
+
+use super::{auth_layer::AuthLogoutExtension, AuthLogoutResponse};
+
+/// Ends the access token of the request, but keeps the refresh token, e.g., to make the client
+/// re-establish its access token with the refresh token after a change of its permissions.
+/// The auth layer revokes the access token with `revoke_access_token` and clears the access token
+/// cookie, the refresh token and its cookie are left untouched.
+#[derive(Clone)]
+pub struct AccessTokenExpireResponse(AuthLogoutResponse);
+
+impl AccessTokenExpireResponse {
+    /// Clears the access token cookie at the given path, at the path of the auth layer if `None`,
+    /// see `AuthLayer::with_access_token_path`.
+    pub fn new(access_token_path: Option<impl Into<String>>) -> Self {
+        let mut auth_logout_response = AuthLogoutResponse::new(access_token_path, None::<String>);
+        auth_logout_response.refresh_token_paths = Vec::new();
+        auth_logout_response.keep_refresh_token = true;
+        Self(auth_logout_response)
+    }
+
+    /// Clears the access token cookie at every given path, see `AuthLogoutResponse::with_paths`.
+    pub fn with_paths(access_token_paths: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let mut auth_logout_response =
+            AuthLogoutResponse::with_paths(access_token_paths, Vec::<String>::new());
+        auth_logout_response.keep_refresh_token = true;
+        Self(auth_logout_response)
+    }
+
+    /// Sets the Domain attribute of the cleared cookie, see `AuthLogoutResponse::with_domain`.
+    pub fn with_domain(self, domain: impl Into<String>) -> Self {
+        Self(self.0.with_domain(domain))
+    }
+
+    /// Sets the SameSite attribute of the cleared cookie, see `AuthLogoutResponse::same_site`.
+    pub fn same_site(self, same_site: SameSite) -> Self {
+        Self(self.0.same_site(same_site))
+    }
+
+    /// Sets the Secure attribute of the cleared cookie, see `AuthLogoutResponse::secure`.
+    pub fn secure(self, secure: bool) -> Self {
+        Self(self.0.secure(secure))
+    }
+}
+
+impl IntoResponseParts for AccessTokenExpireResponse {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(AuthLogoutExtension(self.0));
+
+        Ok(res)
+    }
+}
+
+impl IntoResponse for AccessTokenExpireResponse {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
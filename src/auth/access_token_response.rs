@@ -1,10 +1,13 @@
 use std::convert::Infallible;
 
 use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
-use time::OffsetDateTime;
+use axum_extra::extract::cookie::SameSite;
+use time::{OffsetDateTime, PrimitiveDateTime};
 use tokio::time::Duration;
 
-use super::{token_response::TokenResponse, AccessToken};
+use super::{
+    token_response::TokenResponse, AccessToken, SecureRandomTokenGenerator, TokenGenerator,
+};
 
 #[derive(Debug, Clone)]
 pub struct AccessTokenResponse(pub(super) TokenResponse<AccessToken>);
@@ -32,6 +35,66 @@ impl AccessTokenResponse {
         ))
     }
 
+    /// Creates a response setting the access token in a session cookie, i.e., without an
+    /// expiration date, so the browser drops it when it is closed, e.g., for logins without
+    /// "remember me". Renew such tokens with `RenewalDecision::RenewSession` to keep the cookie
+    /// a session cookie. `expires_at` is the maximal date, as the cookie never expires by date.
+    pub fn session(token: impl Into<AccessToken>, path: Option<&str>) -> Self {
+        let mut access_token_response =
+            Self::with_offset_date_time(token, PrimitiveDateTime::MAX.assume_utc(), path);
+        access_token_response.0.session = true;
+        access_token_response
+    }
+
+    /// Creates a response with a new access token generated by `SecureRandomTokenGenerator`.
+    pub fn generate(expiration_time_delta: Duration, path: Option<&str>) -> Self {
+        Self::with_time_delta(
+            SecureRandomTokenGenerator::default().generate_token(),
+            expiration_time_delta,
+            path,
+        )
+    }
+
+    /// Sets the SameSite attribute of the access token cookie, by default it is the one of the auth
+    /// layer, see `AuthLayer::with_access_token_same_site`.
+    /// `SameSite::None` lets an SPA served from another origin send the cookie, such a cookie is
+    /// issued `Secure` even with `AuthLayer::with_secure_cookies(false)`, as browsers require it
+    /// for `SameSite::None`.
+    /// The auth layer renews the cookie with its own SameSite attribute, so an attribute that has
+    /// to persist across renewals is set on the auth layer instead. The auth layer issues
+    /// `SameSite::Strict` cookies as `SameSite::Lax`, see `AuthLayer::with_lax_access_token_cookie`.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.0.same_site = Some(same_site);
+        self
+    }
+
+    /// Sets the path of the access token cookie, by default it is the one of the auth layer, see
+    /// `AuthLayer::with_access_token_path`.
+    /// The auth layer renews the cookie at its own path, so a path that has to persist across
+    /// renewals is set on the auth layer instead.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.0.path = Some(path.into());
+        self
+    }
+
+    /// Sets the Domain attribute of the access token cookie, by default it is the one of the auth
+    /// layer, see `AuthLayer::with_cookie_domain`, which is not set by default, so the cookie is
+    /// sent to the origin host only.
+    /// The auth layer renews the cookie with its own domain, so a domain that has to persist across
+    /// renewals is set on the auth layer instead. The cookie is cleared at logout only if it is
+    /// cleared with the same domain, see `AuthLogoutResponse::with_domain`.
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.0.domain = Some(domain.into());
+        self
+    }
+
+    /// Targets the auth layer whose access token cookie has the given name, see
+    /// `AuthLayer::with_cookie_names`. The innermost auth layer sets the cookie by default.
+    pub fn with_cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.0.cookie_name = Some(cookie_name.into());
+        self
+    }
+
     pub fn token(&self) -> &AccessToken {
         &self.0.token
     }
@@ -40,8 +103,28 @@ impl AccessTokenResponse {
         &self.0.expires_at
     }
 
+    /// Time left until the token expires, zero if it is already expired.
+    pub fn expires_in(&self) -> Duration {
+        self.0.expires_in()
+    }
+
+    /// The path set by the constructor or `with_path`, `/` if none is set, in which case the auth
+    /// layer sets the cookie at its own path.
     pub fn path(&self) -> &str {
-        &self.0.path
+        self.0.path.as_deref().unwrap_or("/")
+    }
+
+    pub fn domain(&self) -> Option<&str> {
+        self.0.domain.as_deref()
+    }
+
+    pub fn cookie_name(&self) -> Option<&str> {
+        self.0.cookie_name.as_deref()
+    }
+
+    /// Tells whether the access token is set in a session cookie, see `session`.
+    pub fn is_session(&self) -> bool {
+        self.0.session
     }
 }
 
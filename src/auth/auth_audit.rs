@@ -0,0 +1,29 @@
+use super::AccessTokenResponse;
+
+/// Hooks the auth layer calls on logins and logouts, e.g., to write an audit log in one place
+/// instead of in every login and logout handler. Every hook is a no-op by default.
+pub trait AuthAudit<LoginInfoType>: Send + Sync + 'static {
+    /// Called when the auth layer sets an access token cookie from an `AccessTokenResponse` of a
+    /// handler, e.g., at login, even if the request carries a valid refresh token. The issued
+    /// access token is not verified by the auth layer, the hook identifies the user by the
+    /// response. `login_info` is the one of the valid access token of the request, e.g., at a
+    /// re-login, `None` for a request without one.
+    fn on_login(
+        &self,
+        _access_token_response: &AccessTokenResponse,
+        _login_info: Option<&LoginInfoType>,
+    ) {
+    }
+
+    /// Same as `on_login`, but for the access token of a refresh token rotated on a
+    /// `RefreshTokenRotationResponse` of a handler, see `AuthHandler::rotate_refresh_token`.
+    fn on_refresh(
+        &self,
+        _access_token_response: &AccessTokenResponse,
+        _login_info: Option<&LoginInfoType>,
+    ) {
+    }
+
+    /// Called when the auth layer handles an `AuthLogoutResponse` of an authenticated request.
+    fn on_logout(&self, _login_info: &LoginInfoType) {}
+}
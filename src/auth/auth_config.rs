@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use axum_extra::extract::cookie::SameSite;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const ACCESS_TOKEN_COOKIE_NAME: &str = "access_token";
+const REFRESH_TOKEN_COOKIE_NAME: &str = "refresh_token";
+
+/// The cookie and renewal settings of `AuthLayer`, see `AuthLayer::with_config`.
+///
+/// The fields match the `with_*` methods of `AuthLayer` of the same name, their defaults are the
+/// ones of `AuthLayer::new`. With the `serde` feature it can be deserialized, e.g., from the
+/// configuration file of the application, missing fields get their default values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct AuthConfig {
+    /// The name of the access token cookie, `access_token` by default.
+    pub access_token_cookie_name: String,
+    /// The name of the refresh token cookie, `refresh_token` by default.
+    pub refresh_token_cookie_name: String,
+    /// Prepended to the path of every cookie set or cleared by the auth layer, empty by default.
+    pub base_path: String,
+    /// Sets the Secure attribute on the token cookies, `true` by default.
+    pub secure: bool,
+    /// The path of the access token cookie, `/` by default.
+    pub access_token_path: String,
+    /// The Domain attribute of the token cookies, not set by default.
+    pub domain: Option<String>,
+    /// Prefixes the name of the access token cookie with `__Host-`, `false` by default.
+    pub host_cookie_prefix: bool,
+    /// Sets the `Partitioned` attribute on the token cookies, `false` by default.
+    pub partitioned_cookies: bool,
+    /// Adds `Cookie` to the `Vary` header of the responses, `true` by default.
+    pub vary_cookie: bool,
+    /// The SameSite attribute of the access token cookie, `SameSite::Strict` by default.
+    #[cfg_attr(feature = "serde", serde(with = "same_site_serde"))]
+    pub access_token_same_site: SameSite,
+    /// Issues the access token cookie `SameSite=Lax` instead of `SameSite=Strict`, `false` by
+    /// default.
+    pub lax_access_token_cookie: bool,
+    /// Appends an `x-auth-cookie-debug` header for each cookie set, `false` by default.
+    pub cookie_debug_header: bool,
+    /// Accepts access tokens expired less than this ago if they are renewed, zero by default.
+    pub access_token_grace_period: Duration,
+    /// Rejects access tokens without a lifetime, `false` by default.
+    pub access_token_expiry_required: bool,
+    /// Renews the access token only when less than this fraction of its lifetime remains,
+    /// on every request by default.
+    pub renewal_threshold: Option<f64>,
+    /// Verifies up to 4 access token cookies concurrently, `false` by default.
+    pub parallel_access_token_verification: bool,
+    /// Responds with `500` to the panics of the inner service, `false` by default.
+    pub catch_panic: bool,
+    /// Rejects requests carrying an invalid access token, `false` by default.
+    pub reject_invalid_access_token: bool,
+    /// Gives a body to the bodiless `400`, `401` and `403` responses, `false` by default.
+    pub negotiated_error_responses: bool,
+    /// Redirects browsers to this path instead of answering `401`, no redirect by default.
+    pub login_redirect: Option<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            access_token_cookie_name: ACCESS_TOKEN_COOKIE_NAME.into(),
+            refresh_token_cookie_name: REFRESH_TOKEN_COOKIE_NAME.into(),
+            base_path: String::new(),
+            secure: true,
+            access_token_path: "/".into(),
+            domain: None,
+            host_cookie_prefix: false,
+            partitioned_cookies: false,
+            vary_cookie: true,
+            access_token_same_site: SameSite::Strict,
+            lax_access_token_cookie: false,
+            cookie_debug_header: false,
+            access_token_grace_period: Duration::ZERO,
+            access_token_expiry_required: false,
+            renewal_threshold: None,
+            parallel_access_token_verification: false,
+            catch_panic: false,
+            reject_invalid_access_token: false,
+            negotiated_error_responses: false,
+            login_redirect: None,
+        }
+    }
+}
+
+impl AuthConfig {
+    /// Checks the cookie settings, `AuthLayer` checks them when it is layered, so a
+    /// misconfiguration fails when the router is built instead of at the first response.
+    /// The cookie names have to be non-empty valid cookie names and differ from each other, a
+    /// `__Host-` prefixed access token cookie has to be at `/` and `__Secure-` or `__Host-`
+    /// prefixed cookie names and a `SameSite::None` access token cookie need the Secure attribute.
+    pub fn validate(&self) -> Result<(), String> {
+        for cookie_name in [
+            &self.access_token_cookie_name,
+            &self.refresh_token_cookie_name,
+        ] {
+            if !is_cookie_name(cookie_name) {
+                return Err(format!("Invalid cookie name, name = {cookie_name:?}"));
+            }
+            if !self.secure
+                && (cookie_name.starts_with("__Secure-") || cookie_name.starts_with("__Host-"))
+            {
+                return Err(format!(
+                    "A prefixed cookie name needs the Secure attribute, name = {cookie_name}"
+                ));
+            }
+        }
+        if self.access_token_cookie_name == self.refresh_token_cookie_name {
+            return Err(format!(
+                "The access token and the refresh token cookies have the same name, name = {}",
+                self.access_token_cookie_name
+            ));
+        }
+        if !self.secure && self.access_token_same_site == SameSite::None {
+            return Err(
+                "A SameSite=None access token cookie needs the Secure attribute".to_string(),
+            );
+        }
+        if self.host_cookie_prefix && self.access_token_path != "/" {
+            return Err(format!(
+                "The path of a __Host- prefixed access token cookie must be /, path = {}",
+                self.access_token_path
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// A cookie name is a token, i.e., visible ASCII characters except the separators.
+fn is_cookie_name(cookie_name: &str) -> bool {
+    !cookie_name.is_empty()
+        && cookie_name
+            .bytes()
+            .all(|byte| byte.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&byte))
+}
+
+// `SameSite` has no serde support, it is (de)serialized as the value of the cookie attribute
+#[cfg(feature = "serde")]
+mod same_site_serde {
+    use axum_extra::extract::cookie::SameSite;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<SerializerType: Serializer>(
+        same_site: &SameSite,
+        serializer: SerializerType,
+    ) -> Result<SerializerType::Ok, SerializerType::Error> {
+        serializer.serialize_str(&same_site.to_string())
+    }
+
+    pub(super) fn deserialize<'de, DeserializerType: Deserializer<'de>>(
+        deserializer: DeserializerType,
+    ) -> Result<SameSite, DeserializerType::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "Strict" => Ok(SameSite::Strict),
+            "Lax" => Ok(SameSite::Lax),
+            "None" => Ok(SameSite::None),
+            other => Err(Error::unknown_variant(other, &["Strict", "Lax", "None"])),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::{future::Future, pin::Pin};
+
+use axum::{
+    extract::FromRequestParts,
+    http::{Extensions, StatusCode},
+};
+
+use super::auth_layer::AuthContextExtension;
+
+/// Typed values derived by the auth handler from the login info of an authenticated request,
+/// e.g., a permission set or a tenant id.
+/// Handlers read the values with `AuthContextExtractor`.
+#[derive(Clone, Default)]
+pub struct AuthContext(Extensions);
+
+impl AuthContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value, replacing the previous value of the same type.
+    pub fn insert<ValueType: Clone + Send + Sync + 'static>(&mut self, value: ValueType) {
+        self.0.insert(value);
+    }
+
+    pub fn get<ValueType: Send + Sync + 'static>(&self) -> Option<&ValueType> {
+        self.0.get()
+    }
+}
+
+/// Extracts a value the auth handler inserted into the `AuthContext` of the request.
+///
+/// Rejects with `401 Unauthorized` if the request is not authenticated and with
+/// `500 Internal Server Error` if the auth handler did not insert a value of the given type.
+pub struct AuthContextExtractor<ValueType: Clone + Send + Sync + 'static>(pub ValueType);
+
+impl<StateType, ValueType> FromRequestParts<StateType> for AuthContextExtractor<ValueType>
+where
+    ValueType: Clone + Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let value = parts
+            .extensions
+            .get::<AuthContextExtension>()
+            .ok_or(StatusCode::UNAUTHORIZED)
+            .and_then(|auth_context_extension| {
+                auth_context_extension
+                    .0
+                    .get::<ValueType>()
+                    .cloned()
+                    .map(AuthContextExtractor)
+                    .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+            });
+
+        Box::pin(async move { value })
+    }
+}
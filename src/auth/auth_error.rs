@@ -0,0 +1,112 @@
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use http_body::Body as _;
+
+/// Errors of the authentication, e.g., returned by `AuthHandler` implementations through their
+/// `StatusCode` conversion.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum AuthError {
+    /// The request does not contain the required token.
+    MissingToken,
+    /// The access token is unknown, expired or revoked.
+    InvalidAccessToken,
+    /// The refresh token is unknown, expired or revoked.
+    InvalidRefreshToken,
+    /// The user is authenticated, but not permitted to access the resource.
+    Forbidden,
+}
+
+impl From<AuthError> for StatusCode {
+    fn from(auth_error: AuthError) -> Self {
+        match auth_error {
+            AuthError::MissingToken => StatusCode::UNAUTHORIZED,
+            AuthError::InvalidAccessToken => StatusCode::BAD_REQUEST,
+            AuthError::InvalidRefreshToken => StatusCode::BAD_REQUEST,
+            AuthError::Forbidden => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        StatusCode::from(self).into_response()
+    }
+}
+
+/// Gives a body negotiated by the `Accept` header to a bodiless `400`, `401` or `403` response,
+/// other responses are returned untouched.
+pub(super) fn negotiate_error_response(
+    response: Response,
+    accept: Option<&HeaderValue>,
+) -> Response {
+    let status_code = response.status();
+    if !matches!(
+        status_code,
+        StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ) || response.headers().contains_key(CONTENT_TYPE)
+        || response.body().size_hint().exact() != Some(0)
+    {
+        return response;
+    }
+
+    let reason = status_code.canonical_reason().unwrap_or("Error");
+    let (content_type, body) = if accept
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(prefers_html)
+    {
+        (
+            "text/html; charset=utf-8",
+            format!(
+                "<!DOCTYPE html><html><head><title>{status_code}</title></head>\
+                 <body><h1>{status_code}</h1></body></html>"
+            ),
+        )
+    } else {
+        (
+            "application/json",
+            format!(
+                r#"{{"error":"{}"}}"#,
+                reason.to_ascii_lowercase().replace(' ', "_")
+            ),
+        )
+    };
+
+    let (mut parts, _body) = response.into_parts();
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Tells whether the `Accept` header value prefers `text/html` to `application/json`, ties go
+/// to the one listed first.
+pub(super) fn prefers_html(accept: &str) -> bool {
+    let quality_of = |media_type: &str| {
+        accept
+            .split(',')
+            .enumerate()
+            .find_map(|(index, media_range)| {
+                let mut params = media_range.split(';');
+                if !params.next()?.trim().eq_ignore_ascii_case(media_type) {
+                    return None;
+                }
+                let quality = params
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|quality| quality.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((quality, index))
+            })
+    };
+
+    match (quality_of("text/html"), quality_of("application/json")) {
+        (Some((html_quality, html_index)), Some((json_quality, json_index))) => {
+            html_quality > json_quality || (html_quality == json_quality && html_index < json_index)
+        }
+        (Some((html_quality, _)), None) => html_quality > 0.0,
+        _ => false,
+    }
+}
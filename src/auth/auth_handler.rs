@@ -1,9 +1,21 @@
-use std::{borrow::Borrow, ops::Deref, sync::Arc};
+use std::{
+    borrow::Borrow,
+    convert::Infallible,
+    ops::{Deref, Range},
+    str::FromStr,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
-use axum::http::StatusCode;
+use axum::{
+    http::{request::Parts, StatusCode},
+    BoxError,
+};
+use time::OffsetDateTime;
 use tokio::time::Duration;
 
+use super::{AuthContext, RefreshTokenRotation};
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct AccessToken(pub(super) String);
 
@@ -45,6 +57,26 @@ impl AsRef<str> for AccessToken {
     }
 }
 
+impl From<String> for AccessToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl From<&str> for AccessToken {
+    fn from(token: &str) -> Self {
+        Self(token.to_string())
+    }
+}
+
+impl FromStr for AccessToken {
+    type Err = Infallible;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Ok(Self(token.to_string()))
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct RefreshToken(pub(super) String);
 
@@ -86,21 +118,134 @@ impl AsRef<str> for RefreshToken {
     }
 }
 
+impl From<String> for RefreshToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl From<&str> for RefreshToken {
+    fn from(token: &str) -> Self {
+        Self(token.to_string())
+    }
+}
+
+impl FromStr for RefreshToken {
+    type Err = Infallible;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        Ok(Self(token.to_string()))
+    }
+}
+
+/// Decides what happens with the access token cookie of a request carrying a valid access token.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RenewalDecision {
+    /// Sends the given access token to the client with the given expiration time.
+    Renew(AccessToken, Duration),
+    /// Sends the given access token to the client in a session cookie, i.e., without an
+    /// expiration date, so the browser drops it when it is closed, see `AccessTokenResponse::session`.
+    RenewSession(AccessToken),
+    /// Leaves the cookie of the client unchanged, no Set-Cookie header is sent.
+    Keep,
+    /// Clears the access token cookie of the client, e.g., to end the session mid-request.
+    Expire,
+    /// The access token could not be renewed, e.g., the token store is unavailable. The cookie of
+    /// the client is left unchanged like with `Keep`, but the failure can be reported to the
+    /// client with `AuthLayer::with_renewal_failure_header`.
+    Failed,
+}
+
 #[async_trait]
 pub trait AuthHandler<LoginInfoType: Send + Sync>: Sized + Clone + Send + Sync + 'static {
+    /// Ready is called before the servers of an `AxumApp` are bound, if the handler is registered
+    /// with `AxumApp::with_auth_validation`, e.g., to check that the token store is reachable, so a
+    /// misconfiguration fails at startup instead of at the first request.
+    async fn ready(&self) -> Result<(), BoxError> {
+        Ok(())
+    }
+
     /// Update access token is called for every request that contains a access token
     async fn verify_access_token(
         &mut self,
         access_token: &AccessToken,
     ) -> Result<LoginInfoType, StatusCode>;
 
-    /// Update access token is called for every request that contains a valid access token.
-    /// The returned access token is sent for the client.
+    /// Same as `verify_access_token`, but it also receives the parts of the request, e.g., to
+    /// compare the client attributes the access token is bound to with the ones of the request.
+    /// The auth layer calls this method, it calls `verify_access_token` by default.
+    /// Successful verifications cached by the auth layer skip this method as well, so a check
+    /// that has to run on every request, e.g., token binding, belongs in `check_access_token_parts`.
+    async fn verify_access_token_with_parts(
+        &mut self,
+        access_token: &AccessToken,
+        _parts: &Parts,
+    ) -> Result<LoginInfoType, StatusCode> {
+        self.verify_access_token(access_token).await
+    }
+
+    /// Check access token parts is called for every request whose access token was verified successfully, cached verifications included.
+    /// It compares the request with the login info, e.g., the client attributes the access token is bound to with the ones of the request,
+    /// an error treats the access token as invalid.
+    async fn check_access_token_parts(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &LoginInfoType,
+        _parts: &Parts,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    /// Is token acceptable is called for every request whose access token was verified successfully, cached verifications included.
+    /// Returning false treats the request as unauthenticated, e.g., to reject the tokens issued before a forced logout of every user.
+    fn is_token_acceptable(&self, _login_info: &LoginInfoType) -> bool {
+        true
+    }
+
+    /// Extend auth context is called for every request that contains a valid access token, before the request handler runs.
+    /// The values inserted into the context can be extracted by the request handler with `AuthContextExtractor`.
+    async fn extend_auth_context(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfoType>,
+        _auth_context: &mut AuthContext,
+    ) {
+    }
+
+    /// Access token lifetime is called for every request that contains a valid access token, if the auth layer has a
+    /// renewal threshold, see `AuthLayer::with_renewal_threshold`, an access token grace period, see
+    /// `AuthLayer::with_access_token_grace_period`, or requires an expiry, see
+    /// `AuthLayer::with_access_token_expiry_required`. It returns when the access token was issued and when it expires.
+    /// `None`, the default, calls `update_access_token` regardless of the threshold and ignores the grace period, the
+    /// access token is rejected with `AuthLayer::with_access_token_expiry_required`.
+    async fn access_token_lifetime(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfoType>,
+    ) -> Option<Range<OffsetDateTime>> {
+        None
+    }
+
+    /// Authenticated at is called for every request that contains a valid access token. It returns when the user
+    /// authenticated the session of the access token, e.g., entered the password, which stays the same when the access
+    /// token is renewed. `None`, the default, means unknown, `RecentlyAuthenticated` rejects such requests.
+    async fn authenticated_at(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfoType>,
+    ) -> Option<OffsetDateTime> {
+        None
+    }
+
+    /// Update access token is called for every request that contains a valid access token, before the request handler runs.
+    /// The returned decision controls whether the access token cookie is renewed, kept or cleared.
+    /// If the request handler responds with a logout or with its own access token, the decision is
+    /// discarded and a renewed access token other than the received one is passed to `revoke_access_token`.
     async fn update_access_token(
         &mut self,
         access_token: &AccessToken,
         login_info: &Arc<LoginInfoType>,
-    ) -> Option<(AccessToken, Duration)>;
+    ) -> RenewalDecision;
 
     /// Revoke access token is called when the auth layer receives a logout response from a request handler.
     async fn revoke_access_token(
@@ -110,6 +255,8 @@ pub trait AuthHandler<LoginInfoType: Send + Sync>: Sized + Clone + Send + Sync +
     );
 
     /// Verify refresh token is called for every request that contains a refresh token.
+    /// The returned status code is the rejection of `RefreshTokenExtractor`, e.g., `401 Unauthorized` for an expired
+    /// refresh token, so the client logs in again, and `400 Bad Request` for an unknown one.
     async fn verify_refresh_token(
         &mut self,
         refresh_token: &RefreshToken,
@@ -117,4 +264,13 @@ pub trait AuthHandler<LoginInfoType: Send + Sync>: Sized + Clone + Send + Sync +
 
     /// Revoke refresh token is called when the auth layer receives a logout response from a request handler.
     async fn revoke_refresh_token(&mut self, refresh_token: &RefreshToken);
+
+    /// Rotate refresh token is called when the auth layer receives a refresh token rotation response from a request handler.
+    /// It is called even if the verification of the refresh token failed, so the reuse of an already rotated refresh token can be detected.
+    async fn rotate_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> RefreshTokenRotation {
+        RefreshTokenRotation::Rejected(StatusCode::NOT_IMPLEMENTED)
+    }
 }
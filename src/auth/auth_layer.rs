@@ -1,31 +1,47 @@
 use std::{
     future::Future,
     marker::PhantomData,
+    ops::Range,
+    panic::AssertUnwindSafe,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex, Once, PoisonError},
     task::{Context, Poll},
+    time::{Duration, SystemTime},
 };
 
 use axum::{
     extract::Request,
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    http::{
+        header::{ACCEPT, COOKIE, SET_COOKIE, VARY},
+        request::Parts,
+        Extensions, HeaderMap, HeaderName, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Redirect, Response},
 };
 use axum_extra::extract::{
     cookie::{Cookie, SameSite},
     CookieJar,
 };
+use futures_util::{future::join_all, FutureExt};
 use http_body::Body;
 use time::OffsetDateTime;
 use tower::{Layer, Service};
+use tracing::Instrument;
 
 use super::{
-    auth_handler::{AccessToken, RefreshToken},
-    AccessTokenResponse, AuthHandler, AuthLogoutResponse, RefreshTokenResponse,
+    auth_error::{negotiate_error_response, prefers_html},
+    auth_handler::{AccessToken, RefreshToken, RenewalDecision},
+    verification_cache::VerificationCache,
+    AccessTokenResponse, AuthAudit, AuthConfig, AuthContext, AuthHandler, AuthLogoutResponse,
+    PublicRoute, RefreshTokenResponse, RefreshTokenRotation, RefreshTokenRotationResponse,
+    TokenSource,
 };
 
-const ACCESS_TOKEN_COOKIE_NAME: &str = "access_token";
-const REFRESH_TOKEN_COOKIE_NAME: &str = "refresh_token";
+/// At most this many access token cookies of a request are verified, see
+/// `AuthLayer::with_parallel_access_token_verification`.
+const MAX_PARALLEL_ACCESS_TOKEN_VERIFICATIONS: usize = 4;
+const COOKIE_DEBUG_HEADER_NAME: HeaderName = HeaderName::from_static("x-auth-cookie-debug");
+const HOST_COOKIE_PREFIX: &str = "__Host-";
 
 pub(super) struct AccessTokenVerificationResultExtension<LoginInfoType: Send + Sync + 'static>(
     pub(super) Result<Arc<LoginInfoType>, StatusCode>,
@@ -39,52 +55,335 @@ impl<LoginInfoType: Send + Sync + 'static> Clone
     }
 }
 
+pub(super) struct TenantExtension<TenantType: Send + Sync + 'static>(pub(super) Arc<TenantType>);
+
+impl<TenantType: Send + Sync + 'static> Clone for TenantExtension<TenantType> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
 #[derive(Clone)]
 pub(super) struct RefreshTokenVerificationResultExtension(
     pub(super) (RefreshToken, Result<(), StatusCode>),
 );
 
+#[derive(Clone)]
+pub(super) struct AuthContextExtension(pub(super) AuthContext);
+
+#[derive(Clone)]
+pub(super) struct AuthenticatedAtExtension(pub(super) OffsetDateTime);
+
+// read by `FormCsrfLayer`
+#[cfg(feature = "form-csrf")]
+#[derive(Clone)]
+pub(super) struct VerifiedAccessTokenExtension(pub(super) AccessToken);
+
+#[derive(Clone)]
+pub(super) struct AccessTokenRenewedExtension(pub(super) bool);
+
 #[derive(Clone)]
 pub(super) struct AuthLogoutExtension(pub(super) AuthLogoutResponse);
 
-pub fn is_cookie_expired_by_date(cookie: &Cookie) -> bool {
+#[derive(Clone, Default)]
+pub(super) struct SessionControlExtension(pub(super) Arc<Mutex<Option<AuthLogoutResponse>>>);
+
+#[derive(Clone)]
+pub(super) struct RefreshTokenRotationExtension(pub(super) RefreshTokenRotationResponse);
+
+/// Tells whether the cookie is expired at `now` according to its `Expires` attribute, a cookie
+/// expiring exactly at `now` is not expired yet.
+///
+/// Cookies without an `Expires` attribute (session cookies) never expire by date, they are
+/// treated as valid. Note that the cookies of a `Cookie` request header never carry an `Expires`
+/// attribute, browsers send only the names and the values, so the expiration of the cookies
+/// received by the auth layer cannot be checked, the access token itself has to be verified by
+/// `AuthHandler::verify_access_token`. `AuthLayer::with_access_token_expiry_required` rejects the
+/// access tokens without a lifetime instead.
+pub fn is_cookie_expired_at(cookie: &Cookie, now: SystemTime) -> bool {
     if let Some(date_time) = cookie.expires_datetime() {
-        let now = std::time::SystemTime::now();
         return date_time < now;
     }
 
     false
 }
 
+/// Same as `is_cookie_expired_at` with the current time.
+pub fn is_cookie_expired_by_date(cookie: &Cookie) -> bool {
+    is_cookie_expired_at(cookie, SystemTime::now())
+}
+
+/// Parses the cookies of the `Cookie` request headers like `CookieJar::from_headers`, but keeps
+/// every cookie of the same name, e.g., the cookies of different paths, instead of the last one.
+fn request_cookies(headers: &HeaderMap) -> impl Iterator<Item = Cookie<'static>> + '_ {
+    headers
+        .get_all(COOKIE)
+        .into_iter()
+        .filter_map(|header_value| header_value.to_str().ok())
+        .flat_map(|header_value| header_value.split(';'))
+        .filter_map(|cookie| Cookie::parse_encoded(cookie.to_owned()).ok())
+}
+
+/// A top-level navigation coming from another site, according to the fetch metadata headers,
+/// browsers do not send `SameSite=Strict` cookies on it.
+fn is_cross_site_navigation(headers: &HeaderMap) -> bool {
+    headers
+        .get("sec-fetch-site")
+        .is_some_and(|sec_fetch_site| sec_fetch_site == "cross-site")
+        && headers
+            .get("sec-fetch-mode")
+            .is_some_and(|sec_fetch_mode| sec_fetch_mode == "navigate")
+}
+
+/// The SameSite attribute of an access token cookie, `SameSite::Lax` instead of
+/// `SameSite::Strict` if `lax` is set, see `AuthLayer::with_lax_access_token_cookie`.
+fn access_token_cookie_same_site(same_site: SameSite, lax: bool) -> SameSite {
+    match same_site {
+        SameSite::Strict if lax => SameSite::Lax,
+        same_site => same_site,
+    }
+}
+
+/// Builds an access token cookie with the attributes of `config`, the Domain attribute is not set
+/// on a `__Host-` prefixed cookie.
 pub(super) fn create_access_token_cookie<'a>(
+    cookie_name: impl Into<String>,
     access_token: impl Into<String>,
     expires_at: OffsetDateTime,
     path: impl Into<String>,
+    same_site: SameSite,
+    config: &AuthConfig,
 ) -> Cookie<'a> {
-    Cookie::build((ACCESS_TOKEN_COOKIE_NAME, access_token.into()))
+    let partitioned = config.partitioned_cookies;
+    // partitioned cookies are meant for third-party contexts, which require SameSite=None
+    let same_site = if partitioned {
+        SameSite::None
+    } else {
+        same_site
+    };
+    let mut cookie = Cookie::build((cookie_name.into(), access_token.into()))
         .http_only(true)
-        .secure(true)
-        .same_site(SameSite::Strict)
+        // SameSite=None and __Host- prefixed cookies have to stay Secure, browsers reject them
+        // otherwise
+        .secure(config.secure || same_site == SameSite::None || config.host_cookie_prefix)
+        .same_site(same_site)
+        .partitioned(partitioned)
         .expires(expires_at)
         .path(path.into())
-        .build()
+        .build();
+    if let (Some(domain), false) = (&config.domain, config.host_cookie_prefix) {
+        cookie.set_domain(domain.clone());
+    }
+    cookie
 }
 
+/// Builds a refresh token cookie with the attributes of `config`.
 pub(super) fn create_refresh_token_cookie<'a>(
+    cookie_name: impl Into<String>,
     refresh_token: impl Into<String>,
     expires_at: OffsetDateTime,
     path: impl Into<String>,
+    config: &AuthConfig,
 ) -> Cookie<'a> {
-    Cookie::build((REFRESH_TOKEN_COOKIE_NAME, refresh_token.into()))
+    let partitioned = config.partitioned_cookies;
+    let mut cookie = Cookie::build((cookie_name.into(), refresh_token.into()))
         .http_only(true)
-        .secure(true)
-        .same_site(SameSite::Strict)
+        .secure(config.secure || partitioned)
+        .same_site(if partitioned {
+            SameSite::None
+        } else {
+            SameSite::Strict
+        })
+        .partitioned(partitioned)
         .expires(expires_at)
         .path(path.into())
-        .build()
+        .build();
+    if let Some(domain) = &config.domain {
+        cookie.set_domain(domain.clone());
+    }
+    cookie
 }
 
-#[derive(Clone)]
+async fn verify_access_token<LoginInfoType, AuthHandlerType>(
+    auth_impl: &mut AuthHandlerType,
+    verification_cache: &Option<Arc<Mutex<VerificationCache<LoginInfoType>>>>,
+    access_token: &AccessToken,
+    parts: &Parts,
+) -> Result<Arc<LoginInfoType>, StatusCode>
+where
+    LoginInfoType: Send + Sync + 'static,
+    AuthHandlerType: AuthHandler<LoginInfoType>,
+{
+    let cached_login_info = verification_cache.as_ref().and_then(|verification_cache| {
+        verification_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(access_token)
+    });
+    let verification_result = if let Some(login_info) = cached_login_info {
+        Ok(login_info)
+    } else {
+        let verification_result = auth_impl
+            .verify_access_token_with_parts(access_token, parts)
+            .await
+            .map(|login_info| Arc::new(login_info));
+
+        if let (Some(verification_cache), Ok(login_info)) =
+            (verification_cache, &verification_result)
+        {
+            verification_cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(access_token.clone(), login_info.clone());
+        }
+
+        verification_result
+    };
+
+    // checked on every request, cached verifications included, so a policy change takes effect
+    // right away and a cached access token stays bound to the request it was issued for
+    let verification_result = match verification_result {
+        Ok(login_info) => auth_impl
+            .check_access_token_parts(access_token, &login_info, parts)
+            .await
+            .map(|()| login_info),
+        Err(status_code) => Err(status_code),
+    };
+    verification_result.and_then(|login_info| {
+        if auth_impl.is_token_acceptable(&login_info) {
+            Ok(login_info)
+        } else {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    })
+}
+
+fn append_vary_cookie(headers: &mut HeaderMap) {
+    let already_varies_on_cookie = headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|header_value| header_value.to_str().ok())
+        .flat_map(|header_value| header_value.split(','))
+        .any(|field_name| {
+            let field_name = field_name.trim();
+            field_name == "*" || field_name.eq_ignore_ascii_case("cookie")
+        });
+
+    if !already_varies_on_cookie {
+        headers.append(VARY, HeaderValue::from_static("Cookie"));
+    }
+}
+
+fn append_set_cookie(headers: &mut HeaderMap, cookie: Cookie<'_>) {
+    if let Ok(header_value) = HeaderValue::from_str(&cookie.encoded().to_string()) {
+        headers.append(SET_COOKIE, header_value);
+    }
+}
+
+/// Appends a cookie debug header for each token cookie set by the response, summarizing the
+/// attributes of the cookie without its value.
+fn append_cookie_debug_headers(headers: &mut HeaderMap, cookie_names: [&str; 2]) {
+    let cookie_debug_header_values: Vec<_> = headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|header_value| Cookie::parse(header_value.to_str().ok()?).ok())
+        .filter(|cookie| cookie_names.contains(&cookie.name()))
+        .filter_map(|cookie| {
+            let mut summary = cookie.name().to_string();
+            if let Some(path) = cookie.path() {
+                summary.push_str(&format!("; Path={path}"));
+            }
+            if let Some(domain) = cookie.domain() {
+                summary.push_str(&format!("; Domain={domain}"));
+            }
+            if let Some(same_site) = cookie.same_site() {
+                summary.push_str(&format!("; SameSite={same_site}"));
+            }
+            if cookie.secure() == Some(true) {
+                summary.push_str("; Secure");
+            }
+            if cookie.http_only() == Some(true) {
+                summary.push_str("; HttpOnly");
+            }
+            if cookie.partitioned() == Some(true) {
+                summary.push_str("; Partitioned");
+            }
+            if let Some(max_age) = cookie.max_age() {
+                summary.push_str(&format!("; Max-Age={}", max_age.whole_seconds()));
+            }
+            if let Some(expires) = cookie.expires_datetime() {
+                summary.push_str(&format!("; Expires={}", expires.unix_timestamp()));
+            }
+            HeaderValue::from_str(&summary).ok()
+        })
+        .collect();
+
+    for cookie_debug_header_value in cookie_debug_header_values {
+        headers.append(COOKIE_DEBUG_HEADER_NAME, cookie_debug_header_value);
+    }
+}
+
+/// Tells whether less than `renewal_threshold` of the lifetime of an access token remains at `now`.
+fn is_renewal_due(
+    lifetime: &Range<OffsetDateTime>,
+    renewal_threshold: f64,
+    now: OffsetDateTime,
+) -> bool {
+    let total = (lifetime.end - lifetime.start).as_seconds_f64();
+    if total <= 0.0 {
+        return true;
+    }
+
+    (lifetime.end - now).as_seconds_f64() / total < renewal_threshold
+}
+
+/// Prepends the base path of the auth layer to the path of a cookie.
+fn cookie_path(base_path: &str, path: &str) -> String {
+    if base_path.is_empty() {
+        path.to_string()
+    } else if path == "/" {
+        base_path.to_string()
+    } else {
+        format!("{base_path}{path}")
+    }
+}
+
+/// Removes the response extension if it is meant for this auth layer, otherwise it is left for
+/// the outer auth layers.
+fn remove_extension_if<ExtensionType: Clone + Send + Sync + 'static>(
+    extensions: &mut Extensions,
+    predicate: impl FnOnce(&ExtensionType) -> bool,
+) -> Option<ExtensionType> {
+    if extensions.get::<ExtensionType>().is_some_and(predicate) {
+        extensions.remove::<ExtensionType>()
+    } else {
+        None
+    }
+}
+
+fn evict_from_verification_cache<LoginInfoType>(
+    verification_cache: &Option<Arc<Mutex<VerificationCache<LoginInfoType>>>>,
+    access_token: &AccessToken,
+) {
+    if let Some(verification_cache) = verification_cache {
+        verification_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(access_token);
+    }
+}
+
+type PublicPathMatcher = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+type TracingUserFn<LoginInfoType> = Arc<dyn Fn(&LoginInfoType) -> String + Send + Sync>;
+// inserts the tenant projected from the login info into the request extensions, so the tenant
+// type does not have to be a type parameter of the layer
+type TenantProjection<LoginInfoType> = Arc<dyn Fn(&LoginInfoType, &mut Extensions) + Send + Sync>;
+
+/// Authenticates the requests by their access tokens and sets, renews and clears the token
+/// cookies in the responses.
+/// The request body is passed to the inner service untouched, so errors reading it, e.g., a client
+/// disconnecting mid-upload, surface in the extractors of the handler, e.g., `Bytes` and `Json`
+/// reject with `400 Bad Request`.
 pub struct AuthLayer<
     LoginInfoType: Send + Sync + 'static,
     AuthHandlerType: AuthHandler<LoginInfoType>,
@@ -92,18 +391,371 @@ pub struct AuthLayer<
     _marker: PhantomData<LoginInfoType>,
 
     auth_impl: AuthHandlerType,
+    verification_cache: Option<Arc<Mutex<VerificationCache<LoginInfoType>>>>,
+    public_path_matcher: Option<PublicPathMatcher>,
+    config: Arc<AuthConfig>,
+    token_sources: Arc<[TokenSource]>,
+    renewal_failure_header_name: Option<HeaderName>,
+    tracing_user_fn: Option<TracingUserFn<LoginInfoType>>,
+    tenant_projection: Option<TenantProjection<LoginInfoType>>,
+    audit: Option<Arc<dyn AuthAudit<LoginInfoType>>>,
+}
+
+// implemented by hand, as deriving it would require `LoginInfoType: Clone`
+impl<LoginInfoType: Send + Sync + 'static, AuthHandlerType: AuthHandler<LoginInfoType>> Clone
+    for AuthLayer<LoginInfoType, AuthHandlerType>
+{
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+
+            auth_impl: self.auth_impl.clone(),
+            verification_cache: self.verification_cache.clone(),
+            public_path_matcher: self.public_path_matcher.clone(),
+            config: self.config.clone(),
+            token_sources: self.token_sources.clone(),
+            renewal_failure_header_name: self.renewal_failure_header_name.clone(),
+            tracing_user_fn: self.tracing_user_fn.clone(),
+            tenant_projection: self.tenant_projection.clone(),
+            audit: self.audit.clone(),
+        }
+    }
 }
 
 impl<LoginInfoType: Send + Sync + 'static, AuthHandlerType: AuthHandler<LoginInfoType>>
     AuthLayer<LoginInfoType, AuthHandlerType>
 {
     pub fn new(auth_impl: AuthHandlerType) -> Self {
+        Self::with_config(auth_impl, AuthConfig::default())
+    }
+
+    /// Creates the layer with the settings recommended for browser apps: the access token cookie
+    /// is `SameSite=Lax`, see `with_lax_access_token_cookie`, so following a link from an email or
+    /// another site to a protected page keeps the user logged in.
+    pub fn recommended_browser_defaults(auth_impl: AuthHandlerType) -> Self {
+        Self::new(auth_impl).with_lax_access_token_cookie(true)
+    }
+
+    /// Creates the layer with the cookie and renewal settings of `config`, `AuthLayer::new` uses
+    /// `AuthConfig::default()`. The settings can still be changed by the `with_*` methods.
+    pub fn with_config(auth_impl: AuthHandlerType, mut config: AuthConfig) -> Self {
+        config.base_path = config.base_path.trim_end_matches('/').into();
         Self {
             _marker: PhantomData,
 
             auth_impl,
+            verification_cache: None,
+            public_path_matcher: None,
+            config: Arc::new(config),
+            token_sources: Arc::new([TokenSource::Cookie]),
+            renewal_failure_header_name: None,
+            tracing_user_fn: None,
+            tenant_projection: None,
+            audit: None,
         }
     }
+
+    /// Returns the cookie and renewal settings of the layer.
+    pub fn config(&self) -> &AuthConfig {
+        &self.config
+    }
+
+    fn config_mut(&mut self) -> &mut AuthConfig {
+        Arc::make_mut(&mut self.config)
+    }
+
+    /// Accepts access tokens that expired less than `grace_period` ago, provided that
+    /// `update_access_token` renews them in the same request, e.g., a request sent right before the
+    /// expiry that arrives right after it. The grace period is zero by default.
+    /// The expiry is the end of `AuthHandler::access_token_lifetime`, as the `Cookie` request header
+    /// does not carry expiration dates, so `verify_access_token` has to accept the access tokens
+    /// expired less than `grace_period` ago. With a grace period the auth layer rejects the access
+    /// tokens expired longer ago, tokens without a lifetime are accepted as verified.
+    pub fn with_access_token_grace_period(mut self, grace_period: Duration) -> Self {
+        self.config_mut().access_token_grace_period = grace_period;
+        self
+    }
+
+    /// Rejects the access tokens without a lifetime, i.e., for which
+    /// `AuthHandler::access_token_lifetime` returns `None`, e.g., for apps that issue every access
+    /// token with an expiry. It is disabled by default, as `access_token_lifetime` returns `None`
+    /// unless the auth handler implements it.
+    /// The `Expires` attribute of the access token cookie cannot be checked instead, as the
+    /// `Cookie` request header carries only the names and the values of the cookies, see
+    /// `is_cookie_expired_at`.
+    pub fn with_access_token_expiry_required(mut self, access_token_expiry_required: bool) -> Self {
+        self.config_mut().access_token_expiry_required = access_token_expiry_required;
+        self
+    }
+
+    /// Accepts the access token from the query parameter `name` when the request has no valid
+    /// access token cookie, e.g., for websocket upgrades of browser clients.
+    /// Query parameters tend to end up in logs, so the access token may leak.
+    /// Access tokens received in the query are not renewed.
+    /// Same as appending `TokenSource::Query(name)` to the token sources.
+    pub fn with_access_token_query_parameter(mut self, name: impl AsRef<str>) -> Self {
+        self.token_sources = self
+            .token_sources
+            .iter()
+            .cloned()
+            .chain([TokenSource::Query(name.as_ref().to_string())])
+            .collect();
+        self
+    }
+
+    /// Sets the places of the request the access token is read from, they are tried in order
+    /// until an access token is verified successfully. Only `TokenSource::Cookie` by default.
+    /// Only access tokens received in a cookie are renewed.
+    pub fn with_token_sources(
+        mut self,
+        token_sources: impl IntoIterator<Item = TokenSource>,
+    ) -> Self {
+        self.token_sources = token_sources.into_iter().collect();
+        self
+    }
+
+    /// Appends `Vary: Cookie` to the responses of requests that carried an access token cookie, so shared
+    /// caches do not serve the response of a user to another user. It is enabled by default.
+    pub fn with_vary_cookie(mut self, vary_cookie: bool) -> Self {
+        self.config_mut().vary_cookie = vary_cookie;
+        self
+    }
+
+    /// Appends an `x-auth-cookie-debug` header to the response for each token cookie the layer
+    /// sets, listing its name and attributes (path, domain, SameSite, Secure, HttpOnly,
+    /// Partitioned, Max-Age and Expires as a unix timestamp), but never its value. It helps to
+    /// find out why a browser rejects a cookie, e.g., a Secure cookie over plain HTTP.
+    /// It is disabled by default and should not be enabled in production.
+    pub fn with_cookie_debug_header(mut self, cookie_debug_header: bool) -> Self {
+        self.config_mut().cookie_debug_header = cookie_debug_header;
+        self
+    }
+
+    /// Gives a body to the bodiless `400`, `401` and `403` responses, e.g., the rejections of the
+    /// auth extractors and `AuthError`, negotiated by the `Accept` header of the request:
+    /// a minimal HTML page if `text/html` is preferred to `application/json`, otherwise
+    /// `{"error":"unauthorized"}` style JSON. It is disabled by default.
+    ///
+    /// The JSON body is an object with a single `error` field holding the snake case reason
+    /// phrase of the status code: `bad_request` for `400`, `unauthorized` for `401` and
+    /// `forbidden` for `403`.
+    pub fn with_negotiated_error_responses(mut self, negotiated_error_responses: bool) -> Self {
+        self.config_mut().negotiated_error_responses = negotiated_error_responses;
+        self
+    }
+
+    /// Redirects with `303 See Other` to `login_path` when the response is `401` and the request
+    /// prefers `text/html` to `application/json`, e.g., a logged-out browser opening a protected
+    /// page of a server-rendered app. Other clients still get the `401`, and the `Set-Cookie`
+    /// headers of the response are kept. No redirect is done by default.
+    pub fn with_login_redirect(mut self, login_path: impl AsRef<str>) -> Self {
+        self.config_mut().login_redirect = Some(login_path.as_ref().into());
+        self
+    }
+
+    /// Verifies every access token cookie of a request concurrently, e.g., when stale cookies of
+    /// other paths or domains are sent along with the fresh one under the same name, and accepts
+    /// the first valid one in the order of the `Cookie` header. By default only one access token
+    /// cookie of the same name is verified. The auth handler is cloned for every cookie.
+    /// To bound the work a request can cause with junk cookies, at most the first 4 access token
+    /// cookies are verified. It is disabled by default.
+    pub fn with_parallel_access_token_verification(
+        mut self,
+        parallel_access_token_verification: bool,
+    ) -> Self {
+        self.config_mut().parallel_access_token_verification = parallel_access_token_verification;
+        self
+    }
+
+    /// Catches the panics of the inner service and responds with `500 Internal Server Error`, as
+    /// to any other `500` response of the inner service the renewed access token cookie is set,
+    /// and a logout requested through `SessionControl` before the panic is carried out, so the
+    /// cookies are consistent with the tokens the auth handler renewed or revoked.
+    /// The panic is logged, but not propagated, so an outer `CatchPanic` layer does not see it.
+    /// It is disabled by default, the panic unwinds through the auth layer and neither the
+    /// renewal nor a requested logout is applied.
+    pub fn with_catch_panic(mut self, catch_panic: bool) -> Self {
+        self.config_mut().catch_panic = catch_panic;
+        self
+    }
+
+    /// Sets the names of the access token and the refresh token cookies, `access_token` and
+    /// `refresh_token` by default.
+    ///
+    /// Auth layers with distinct cookie names and login info types can be stacked, e.g., for
+    /// separate admin and user logins: each layer verifies and renews only its own cookies.
+    /// An `AccessTokenResponse` or a `RefreshTokenResponse` is consumed by the innermost layer,
+    /// unless it targets the cookie of another layer with `with_cookie_name`.
+    /// Logout, refresh token rotation, `RefreshTokenExtractor`, `AuthContextExtractor` and
+    /// `RenewedThisRequest` are handled by the innermost layer only.
+    pub fn with_cookie_names(
+        mut self,
+        access_token_cookie_name: impl AsRef<str>,
+        refresh_token_cookie_name: impl AsRef<str>,
+    ) -> Self {
+        self.config_mut().access_token_cookie_name = access_token_cookie_name.as_ref().into();
+        self.config_mut().refresh_token_cookie_name = refresh_token_cookie_name.as_ref().into();
+        self
+    }
+
+    /// Prefixes the name of the access token cookie with `__Host-`, e.g., `__Host-access_token`,
+    /// browsers only accept such a cookie if it is `Secure`, has no `Domain` and its path is `/`.
+    /// The auth layer sets the cookie accordingly, the path and the domain of an `AccessTokenResponse`
+    /// are ignored, an access token path other than `/` fails the validation of the configuration,
+    /// see `AuthConfig::validate`. The refresh token cookie is not affected.
+    pub fn with_host_cookie_prefix(mut self, host_cookie_prefix: bool) -> Self {
+        self.config_mut().host_cookie_prefix = host_cookie_prefix;
+        self
+    }
+
+    /// Sets the Secure attribute on the token cookies, including the renewed and the cleared ones,
+    /// it is set by default. Unsetting it allows cookies over plain HTTP during development.
+    /// Partitioned and `__Host-` prefixed cookies are `Secure` regardless.
+    pub fn with_secure_cookies(mut self, secure: bool) -> Self {
+        self.config_mut().secure = secure;
+        self
+    }
+
+    /// Sets the path of the access token cookie, `/` by default. The layer sets, renews and clears
+    /// the cookie at this path unless an `AccessTokenResponse` or an `AuthLogoutResponse` gives
+    /// another one, the base path is prepended, see `with_base_path`.
+    pub fn with_access_token_path(mut self, path: impl Into<String>) -> Self {
+        self.config_mut().access_token_path = path.into();
+        self
+    }
+
+    /// Sets the Domain attribute of the token cookies, it is not set by default, so the cookies
+    /// are sent to the origin host only. The layer sets, renews and clears the cookies with this
+    /// domain unless an `AccessTokenResponse` or an `AuthLogoutResponse` gives another one. The
+    /// domain is not set on a `__Host-` prefixed access token cookie.
+    pub fn with_cookie_domain(mut self, domain: impl Into<String>) -> Self {
+        self.config_mut().domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the SameSite attribute of the access token cookies set by the layer, the renewed ones
+    /// included, `SameSite::Strict` by default. An `AccessTokenResponse` can override it with
+    /// `AccessTokenResponse::same_site`, but the renewed cookies get the attribute of the layer,
+    /// e.g., an SPA served from another origin sets `SameSite::None` here.
+    pub fn with_access_token_same_site(mut self, same_site: SameSite) -> Self {
+        self.config_mut().access_token_same_site = same_site;
+        self
+    }
+
+    /// Issues the access token cookie `SameSite=Lax` where it would be `SameSite=Strict`, the
+    /// default, both at login and at renewal. Browsers do not send a `Strict` cookie on a navigation
+    /// coming from another site, e.g., a link in an email, so the user looks logged out. The
+    /// refresh token cookie stays `Strict`, as it is only sent to the refresh endpoint.
+    /// In debug builds, the first `401` to such a navigation without an access token cookie logs a
+    /// hint while the access token cookie is `Strict`.
+    pub fn with_lax_access_token_cookie(mut self, lax_access_token_cookie: bool) -> Self {
+        self.config_mut().lax_access_token_cookie = lax_access_token_cookie;
+        self
+    }
+
+    /// Sets the `Partitioned` attribute (CHIPS) on the access token and the refresh token cookies,
+    /// so browsers keep a separate cookie per top-level site, e.g., for a widget embedded in
+    /// third-party sites. Partitioned cookies are `SameSite=None`, overriding any other SameSite
+    /// setting, and `Secure`, as every cookie of the auth layer.
+    pub fn with_partitioned_cookies(mut self, partitioned_cookies: bool) -> Self {
+        self.config_mut().partitioned_cookies = partitioned_cookies;
+        self
+    }
+
+    /// Responds with the status code of the failed verification when the request carries an
+    /// access token that is not valid, instead of passing the request to the inner service as
+    /// an anonymous one. The access token cookie is cleared in the response.
+    /// It is disabled by default, as e.g., pages serving both anonymous and authenticated users
+    /// rely on the anonymous fall-through.
+    pub fn with_reject_invalid_access_token(mut self, reject_invalid_access_token: bool) -> Self {
+        self.config_mut().reject_invalid_access_token = reject_invalid_access_token;
+        self
+    }
+
+    /// Prepends `base_path` to the path of every cookie set or cleared by the auth layer, e.g.,
+    /// an `AccessTokenResponse` with the default `/` path sets a cookie scoped to `base_path`.
+    ///
+    /// `Router::nest` strips the mount prefix from the path of the requests before they reach
+    /// the nested router, so the auth layer of a nested router cannot learn the prefix itself,
+    /// it has to be set to the prefix of `Router::nest`. The paths seen by the public path matcher
+    /// are not affected, they stay stripped.
+    /// A `__Host-` prefixed access token cookie is always set at `/`, the base path is not
+    /// applied to it.
+    pub fn with_base_path(mut self, base_path: impl AsRef<str>) -> Self {
+        self.config_mut().base_path = base_path.as_ref().trim_end_matches('/').into();
+        self
+    }
+
+    /// Calls `update_access_token` only when less than `remaining_fraction` of the lifetime of the
+    /// access token remains, e.g., `0.5` renews the access token in the second half of its
+    /// lifetime, so the cookie is not rewritten on every request. The lifetime is returned by
+    /// `AuthHandler::access_token_lifetime`, access tokens without a lifetime are always updated.
+    /// By default `update_access_token` is called for every request.
+    pub fn with_renewal_threshold(mut self, remaining_fraction: f64) -> Self {
+        self.config_mut().renewal_threshold = Some(remaining_fraction);
+        self
+    }
+
+    /// Sets the header `header_name: failed` on the response when `update_access_token` returns
+    /// `RenewalDecision::Failed`, so the client can refresh its session proactively instead of
+    /// waiting for the access token to expire. The status code of the response is not changed.
+    /// No header is set by default.
+    pub fn with_renewal_failure_header(mut self, header_name: HeaderName) -> Self {
+        self.renewal_failure_header_name = Some(header_name);
+        self
+    }
+
+    /// Runs the inner service of authenticated requests in an `auth` tracing span, whose `user`
+    /// field is the identifier returned by `tracing_user_fn` for the login info, so every
+    /// tracing event of the request handler carries the user. It is disabled by default.
+    pub fn with_tracing_user(
+        mut self,
+        tracing_user_fn: impl Fn(&LoginInfoType) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.tracing_user_fn = Some(Arc::new(tracing_user_fn));
+        self
+    }
+
+    /// Projects the tenant out of the login info of authenticated requests with `tenant_fn`, once
+    /// per request, so handlers can extract it with `TenantExtractor<TenantType>`.
+    /// No tenant is projected by default.
+    pub fn with_tenant<TenantType: Send + Sync + 'static>(
+        mut self,
+        tenant_fn: impl Fn(&LoginInfoType) -> TenantType + Send + Sync + 'static,
+    ) -> Self {
+        self.tenant_projection = Some(Arc::new(move |login_info, extensions| {
+            extensions.insert(TenantExtension(Arc::new(tenant_fn(login_info))));
+        }));
+        self
+    }
+
+    /// Calls the hooks of `audit` on the logins and logouts handled by the layer.
+    pub fn with_audit(mut self, audit: impl AuthAudit<LoginInfoType>) -> Self {
+        self.audit = Some(Arc::new(audit));
+        self
+    }
+
+    /// Skips authentication for requests whose path is matched by `public_path_matcher`.
+    /// These requests are passed to the inner service untouched, their tokens are not verified
+    /// and no cookies are set or cleared in their responses.
+    pub fn with_public_path_matcher(
+        mut self,
+        public_path_matcher: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.public_path_matcher = Some(Arc::new(public_path_matcher));
+        self
+    }
+
+    /// Caches the results of successful access token verifications for `ttl`, at most `capacity`
+    /// access tokens are cached, the least recently used one is evicted first.
+    /// While an access token is cached `verify_access_token` is not called for it, so a token
+    /// invalidated in the backend stays valid until its cache entry expires. Access tokens revoked
+    /// or expired by the auth layer are evicted immediately. `AuthHandler::check_access_token_parts`
+    /// is called for cached access tokens as well.
+    pub fn with_verification_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.verification_cache = Some(Arc::new(Mutex::new(VerificationCache::new(capacity, ttl))));
+        self
+    }
 }
 
 impl<
@@ -114,17 +766,63 @@ impl<
 {
     type Service = AuthMiddleware<InnerServiceType, LoginInfoType, AuthHandlerType>;
 
+    /// Panics if the configuration is invalid, see `AuthConfig::validate`.
     fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        if let Err(err) = self.config.validate() {
+            panic!("Invalid AuthLayer configuration: {err}");
+        }
+
         AuthMiddleware {
             _marker: PhantomData,
 
             inner,
             auth_impl: self.auth_impl.clone(),
+            verification_cache: self.verification_cache.clone(),
+            public_path_matcher: self.public_path_matcher.clone(),
+            config: self.config.clone(),
+            token_sources: self.token_sources.clone(),
+            renewal_failure_header_name: self.renewal_failure_header_name.clone(),
+            tracing_user_fn: self.tracing_user_fn.clone(),
+            tenant_projection: self.tenant_projection.clone(),
+            audit: self.audit.clone(),
         }
     }
 }
 
-#[derive(Clone)]
+/// The service of `AuthLayer`, wrapping the inner service.
+///
+/// It is a `Service<Request<RequestBodyType>>` for any `RequestBodyType: Body + Send + 'static`,
+/// if the inner service is a `Service<Request<RequestBodyType>>` that is `Clone + Send + 'static`,
+/// whose future is `Send` and whose error is `Send`. The response of the inner service has to be
+/// `IntoResponse + Send`, the middleware always responds with an axum `Response`, and passes the
+/// errors of the inner service on.
+///
+/// ```
+/// use axum::{routing::get, Router};
+/// use axum_helpers::auth::{AuthLayer, AuthMiddleware, FnAuthHandler, RenewalDecision};
+/// use tower::ServiceBuilder;
+///
+/// #[derive(Clone)]
+/// struct LoginInfo;
+///
+/// fn auth_handler() -> FnAuthHandler<LoginInfo> {
+///     FnAuthHandler::new(
+///         |_access_token| Box::pin(async { Ok(LoginInfo) }),
+///         |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+///         |_access_token, _login_info| Box::pin(async {}),
+///     )
+/// }
+///
+/// let inner: Router = Router::new().route("/", get(|| async { "private" }));
+///
+/// // built by the layer in a service builder
+/// let service = ServiceBuilder::new()
+///     .layer(AuthLayer::new(auth_handler()).with_vary_cookie(false))
+///     .service(inner.clone());
+///
+/// // constructed directly, with the defaults of `AuthLayer::new`
+/// let service = AuthMiddleware::new(inner, auth_handler());
+/// ```
 pub struct AuthMiddleware<
     InnerServiceType,
     LoginInfoType: Send + Sync + 'static,
@@ -134,6 +832,52 @@ pub struct AuthMiddleware<
 
     inner: InnerServiceType,
     auth_impl: AuthHandlerType,
+    verification_cache: Option<Arc<Mutex<VerificationCache<LoginInfoType>>>>,
+    public_path_matcher: Option<PublicPathMatcher>,
+    config: Arc<AuthConfig>,
+    token_sources: Arc<[TokenSource]>,
+    renewal_failure_header_name: Option<HeaderName>,
+    tracing_user_fn: Option<TracingUserFn<LoginInfoType>>,
+    tenant_projection: Option<TenantProjection<LoginInfoType>>,
+    audit: Option<Arc<dyn AuthAudit<LoginInfoType>>>,
+}
+
+// implemented by hand, as deriving it would require `LoginInfoType: Clone`
+impl<
+        InnerServiceType: Clone,
+        LoginInfoType: Send + Sync + 'static,
+        AuthHandlerType: AuthHandler<LoginInfoType>,
+    > Clone for AuthMiddleware<InnerServiceType, LoginInfoType, AuthHandlerType>
+{
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+
+            inner: self.inner.clone(),
+            auth_impl: self.auth_impl.clone(),
+            verification_cache: self.verification_cache.clone(),
+            public_path_matcher: self.public_path_matcher.clone(),
+            config: self.config.clone(),
+            token_sources: self.token_sources.clone(),
+            renewal_failure_header_name: self.renewal_failure_header_name.clone(),
+            tracing_user_fn: self.tracing_user_fn.clone(),
+            tenant_projection: self.tenant_projection.clone(),
+            audit: self.audit.clone(),
+        }
+    }
+}
+
+impl<InnerServiceType, LoginInfoType, AuthHandlerType>
+    AuthMiddleware<InnerServiceType, LoginInfoType, AuthHandlerType>
+where
+    LoginInfoType: Send + Sync + 'static,
+    AuthHandlerType: AuthHandler<LoginInfoType>,
+{
+    /// Wraps `inner` with the same configuration as `AuthLayer::new(auth_handler)`, use
+    /// `AuthLayer` to configure the middleware.
+    pub fn new(inner: InnerServiceType, auth_handler: AuthHandlerType) -> Self {
+        AuthLayer::new(auth_handler).layer(inner)
+    }
 }
 
 impl<InnerServiceType, RequestBodyType, InnerResponseType, LoginInfoType, AuthHandlerType>
@@ -157,31 +901,95 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, mut req: Request<RequestBodyType>) -> Self::Future {
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+
+        let public = req.extensions().get::<PublicRoute>().is_some()
+            || self
+                .public_path_matcher
+                .as_ref()
+                .is_some_and(|public_path_matcher| public_path_matcher(req.uri().path()));
+        if public {
+            return Box::pin(async move {
+                inner
+                    .call(req)
+                    .await
+                    .map(|next_response| next_response.into_response())
+            });
+        }
+
+        let config = self.config.clone();
         let mut auth_impl = self.auth_impl.clone();
-        let mut inner = self.inner.clone();
-        Box::pin(async move {
-            let mut received_access_token_login_result_pair = None;
+        let verification_cache = self.verification_cache.clone();
+        let access_token_grace_period = self.config.access_token_grace_period;
+        let access_token_expiry_required = self.config.access_token_expiry_required;
+        let parallel_access_token_verification = self.config.parallel_access_token_verification;
+        let catch_panic = self.config.catch_panic;
+        let token_sources = self.token_sources.clone();
+        let vary_cookie = self.config.vary_cookie;
+        let cookie_debug_header = self.config.cookie_debug_header;
+        let renewal_threshold = self.config.renewal_threshold;
+        let host_cookie_prefix = self.config.host_cookie_prefix;
+        let partitioned = self.config.partitioned_cookies;
+        let lax_access_token_cookie = self.config.lax_access_token_cookie;
+        let access_token_same_site = access_token_cookie_same_site(
+            self.config.access_token_same_site,
+            lax_access_token_cookie,
+        );
+        let reject_invalid_access_token = self.config.reject_invalid_access_token;
+        let base_path = self.config.base_path.clone();
+        let access_token_path = self.config.access_token_path.clone();
+        let renewal_failure_header_name = self.renewal_failure_header_name.clone();
+        let tracing_user_fn = self.tracing_user_fn.clone();
+        let tenant_projection = self.tenant_projection.clone();
+        let audit = self.audit.clone();
+        let access_token_response_cookie_name = self.config.access_token_cookie_name.clone();
+        let access_token_cookie_name = if host_cookie_prefix {
+            format!(
+                "{HOST_COOKIE_PREFIX}{}",
+                self.config.access_token_cookie_name
+            )
+        } else {
+            self.config.access_token_cookie_name.to_string()
+        };
+        let refresh_token_cookie_name = self.config.refresh_token_cookie_name.clone();
+        let negotiated_error_responses_accept = self
+            .config
+            .negotiated_error_responses
+            .then(|| req.headers().get(ACCEPT).cloned());
+        let strict_cross_site_navigation = cfg!(debug_assertions)
+            && !lax_access_token_cookie
+            && is_cross_site_navigation(req.headers())
+            && CookieJar::from_headers(req.headers())
+                .get(&access_token_cookie_name)
+                .is_none();
+        let login_redirect = self.config.login_redirect.clone().filter(|_| {
+            req.headers()
+                .get(ACCEPT)
+                .and_then(|accept| accept.to_str().ok())
+                .is_some_and(prefers_html)
+        });
+        let response_future = async move {
+            // a __Host- prefixed cookie can only exist at /
+            let access_token_cookie_path = |path: &str| {
+                if host_cookie_prefix {
+                    "/".to_string()
+                } else {
+                    cookie_path(&base_path, path)
+                }
+            };
+
+            // the parts are borrowed by the verification, the request is reassembled before it is
+            // passed to the inner service
+            let (mut parts, body) = req.into_parts();
+
             let mut received_refresh_token = None;
-            let cookie_jar = CookieJar::from_headers(req.headers());
+            let cookie_jar = CookieJar::from_headers(&parts.headers);
             for cookie in cookie_jar.iter() {
-                if cookie.name() == ACCESS_TOKEN_COOKIE_NAME && !is_cookie_expired_by_date(cookie) {
-                    let replace = match &received_access_token_login_result_pair {
-                        Some((_access_token, Ok(_login_info))) => false,
-                        Some((_access_token, Err(_))) => true,
-                        None => true,
-                    };
-
-                    if replace {
-                        let access_token = AccessToken(cookie.value().to_string());
-                        let verification_result = auth_impl
-                            .verify_access_token(&access_token)
-                            .await
-                            .map(|login_info| Arc::new(login_info));
-                        received_access_token_login_result_pair =
-                            Some((access_token, verification_result))
-                    }
-                } else if cookie.name() == REFRESH_TOKEN_COOKIE_NAME
+                if cookie.name() == &*refresh_token_cookie_name
                     && !is_cookie_expired_by_date(cookie)
                 {
                     let replace = match &received_refresh_token {
@@ -199,50 +1007,481 @@ where
                 }
             }
 
+            // the token sources are tried in order until an access token is verified successfully
+            let mut received_access_token_login_result_pair = None;
+            let mut access_token_from_cookie = false;
+            for token_source in token_sources.iter() {
+                if matches!(received_access_token_login_result_pair, Some((_, Ok(_)))) {
+                    break;
+                }
+
+                if let TokenSource::Cookie = token_source {
+                    if parallel_access_token_verification {
+                        let candidates = request_cookies(&parts.headers)
+                            .filter(|cookie| cookie.name() == access_token_cookie_name)
+                            .take(MAX_PARALLEL_ACCESS_TOKEN_VERIFICATIONS)
+                            .map(|cookie| AccessToken(cookie.value().to_string()))
+                            .collect::<Vec<_>>();
+                        let mut auth_impls = vec![auth_impl.clone(); candidates.len()];
+                        let verification_results =
+                            join_all(auth_impls.iter_mut().zip(&candidates).map(
+                                |(auth_impl, access_token)| {
+                                    verify_access_token(
+                                        auth_impl,
+                                        &verification_cache,
+                                        access_token,
+                                        &parts,
+                                    )
+                                },
+                            ))
+                            .await;
+
+                        // the first valid access token, otherwise the last invalid one, as the
+                        // sequential verification does
+                        let chosen = candidates.into_iter().zip(verification_results).reduce(
+                            |chosen, candidate| if chosen.1.is_ok() { chosen } else { candidate },
+                        );
+                        if let Some((access_token, verification_result)) = chosen {
+                            access_token_from_cookie = true;
+                            received_access_token_login_result_pair =
+                                Some((access_token, verification_result));
+                        }
+                    } else {
+                        for cookie in cookie_jar.iter() {
+                            if cookie.name() == access_token_cookie_name {
+                                let replace = match &received_access_token_login_result_pair {
+                                    Some((_access_token, Ok(_login_info))) => false,
+                                    Some((_access_token, Err(_))) => true,
+                                    None => true,
+                                };
+
+                                if replace {
+                                    let access_token = AccessToken(cookie.value().to_string());
+                                    let verification_result = verify_access_token(
+                                        &mut auth_impl,
+                                        &verification_cache,
+                                        &access_token,
+                                        &parts,
+                                    )
+                                    .await;
+                                    access_token_from_cookie = true;
+                                    received_access_token_login_result_pair =
+                                        Some((access_token, verification_result))
+                                }
+                            }
+                        }
+                    }
+                } else if let Some(access_token) = token_source.access_token(&parts) {
+                    let verification_result = verify_access_token(
+                        &mut auth_impl,
+                        &verification_cache,
+                        &access_token,
+                        &parts,
+                    )
+                    .await;
+                    access_token_from_cookie = false;
+                    received_access_token_login_result_pair =
+                        Some((access_token, verification_result));
+                }
+            }
+
+            // the request cookies carry no expiration dates, so the grace period is measured from
+            // the end of the lifetime of the access token and a required expiry is checked by the
+            // presence of a lifetime
+            let mut access_token_in_grace_period = false;
+            if access_token_expiry_required || !access_token_grace_period.is_zero() {
+                if let Some((access_token, login_result)) =
+                    &mut received_access_token_login_result_pair
+                {
+                    if let Ok(login_info) = login_result {
+                        let now = OffsetDateTime::now_utc();
+                        match auth_impl
+                            .access_token_lifetime(access_token, login_info)
+                            .await
+                        {
+                            None if access_token_expiry_required => {
+                                *login_result = Err(StatusCode::UNAUTHORIZED);
+                            }
+                            Some(lifetime)
+                                if lifetime.end < now && !access_token_grace_period.is_zero() =>
+                            {
+                                if now - lifetime.end <= access_token_grace_period {
+                                    access_token_in_grace_period = true;
+                                } else {
+                                    *login_result = Err(StatusCode::UNAUTHORIZED);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            // The renewal decision is made before the request handler runs, so the handler can
+            // learn about it through the `RenewedThisRequest` extractor. The decision is applied
+            // after the handler returned, unless the handler responded with its own access token
+            // or with a logout, in which case the decision is discarded.
+            // only access tokens received in a cookie are renewed, as renewal sets a cookie
+            let mut renewal_decision = if let (Some((access_token, Ok(login_info))), true) = (
+                &received_access_token_login_result_pair,
+                access_token_from_cookie,
+            ) {
+                let renewal_due = if let Some(renewal_threshold) = renewal_threshold {
+                    auth_impl
+                        .access_token_lifetime(access_token, login_info)
+                        .await
+                        .is_none_or(|lifetime| {
+                            is_renewal_due(&lifetime, renewal_threshold, OffsetDateTime::now_utc())
+                        })
+                } else {
+                    true
+                };
+
+                if renewal_due {
+                    Some(
+                        auth_impl
+                            .update_access_token(access_token, login_info)
+                            .await,
+                    )
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            // an access token in its grace period is only accepted if it is renewed right away
+            if access_token_in_grace_period
+                && !matches!(
+                    renewal_decision,
+                    Some(RenewalDecision::Renew(..) | RenewalDecision::RenewSession(..))
+                )
+            {
+                if let Some((_access_token, login_result)) =
+                    &mut received_access_token_login_result_pair
+                {
+                    *login_result = Err(StatusCode::UNAUTHORIZED);
+                }
+                renewal_decision = None;
+            }
+
+            if reject_invalid_access_token {
+                if let Some((_access_token, Err(status_code))) =
+                    &received_access_token_login_result_pair
+                {
+                    let mut response = status_code.into_response();
+                    if access_token_from_cookie {
+                        append_set_cookie(
+                            response.headers_mut(),
+                            create_access_token_cookie(
+                                &access_token_cookie_name,
+                                "",
+                                time::OffsetDateTime::UNIX_EPOCH,
+                                access_token_cookie_path(&access_token_path),
+                                access_token_same_site,
+                                &config,
+                            ),
+                        );
+                    }
+                    return Ok(response);
+                }
+            }
+
+            if let Some((access_token, Ok(login_info))) = &received_access_token_login_result_pair {
+                let mut auth_context = AuthContext::new();
+                auth_impl
+                    .extend_auth_context(access_token, login_info, &mut auth_context)
+                    .await;
+                parts.extensions.insert(AuthContextExtension(auth_context));
+                #[cfg(feature = "form-csrf")]
+                parts
+                    .extensions
+                    .insert(VerifiedAccessTokenExtension(access_token.clone()));
+                if let Some(authenticated_at) =
+                    auth_impl.authenticated_at(access_token, login_info).await
+                {
+                    parts
+                        .extensions
+                        .insert(AuthenticatedAtExtension(authenticated_at));
+                }
+            }
+
+            // the query string is not logged, as it may contain sensitive values
+            log::debug!(
+                "Access token verified, method = {}, path = {}, outcome = {}",
+                parts.method,
+                parts.uri.path(),
+                match &received_access_token_login_result_pair {
+                    None => "no_token",
+                    Some((_access_token, Err(_))) => "invalid",
+                    Some((_access_token, Ok(_))) => "valid",
+                }
+            );
+
             if let Some((_at, login_result)) = &received_access_token_login_result_pair {
-                req.extensions_mut()
+                parts
+                    .extensions
                     .insert(AccessTokenVerificationResultExtension(login_result.clone()));
             }
 
+            if let (Some(tenant_projection), Some((_access_token, Ok(login_info)))) =
+                (&tenant_projection, &received_access_token_login_result_pair)
+            {
+                tenant_projection(login_info, &mut parts.extensions);
+            }
+
             if let Some(refresh_token) = &received_refresh_token {
-                req.extensions_mut()
+                parts
+                    .extensions
                     .insert(RefreshTokenVerificationResultExtension(
                         refresh_token.clone(),
                     ));
             }
 
-            let next_response = inner.call(req).await;
+            parts
+                .extensions
+                .insert(AccessTokenRenewedExtension(matches!(
+                    renewal_decision,
+                    Some(RenewalDecision::Renew(..) | RenewalDecision::RenewSession(..))
+                )));
+
+            let tracing_span = match (&tracing_user_fn, &received_access_token_login_result_pair) {
+                (Some(tracing_user_fn), Some((_access_token, Ok(login_info)))) => {
+                    Some(tracing::info_span!("auth", user = %tracing_user_fn(login_info)))
+                }
+                _ => None,
+            };
+
+            let session_control_extension = SessionControlExtension::default();
+            parts.extensions.insert(session_control_extension.clone());
+
+            let req = Request::from_parts(parts, body);
+            let next_response = async move {
+                let next_response = if let Some(tracing_span) = tracing_span {
+                    inner.call(req).instrument(tracing_span).await
+                } else {
+                    inner.call(req).await
+                };
+                next_response.map(|next_response| next_response.into_response())
+            };
+            let next_response = if catch_panic {
+                // the renewal decision was made and the handler may have requested a logout
+                // through `SessionControl` before it panicked, both are still applied
+                match AssertUnwindSafe(next_response).catch_unwind().await {
+                    Ok(next_response) => next_response,
+                    Err(_panic) => {
+                        log::error!("Request handler panicked, responding with 500");
+                        Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+                    }
+                }
+            } else {
+                next_response.await
+            };
 
             match next_response {
                 Ok(next_response) => {
                     let mut response = next_response.into_response();
 
+                    // a logout requested through `SessionControl` is handled as if the handler
+                    // responded with it
+                    let requested_logout = session_control_extension
+                        .0
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .take();
+                    if let Some(auth_logout_response) = requested_logout {
+                        if response.extensions().get::<AuthLogoutExtension>().is_none() {
+                            response = (auth_logout_response, response).into_response();
+                        }
+                    }
+
                     let cookie_jar = CookieJar::new();
 
-                    let access_token_response =
-                        response.extensions_mut().remove::<AccessTokenResponse>();
+                    let mut refresh_token_rotated = false;
+                    let cookie_jar = if let Some(refresh_token_rotation_extension) = response
+                        .extensions_mut()
+                        .remove::<RefreshTokenRotationExtension>()
+                    {
+                        let refresh_token_rotation =
+                            if let Some((refresh_token, _)) = &received_refresh_token {
+                                auth_impl.rotate_refresh_token(refresh_token).await
+                            } else {
+                                RefreshTokenRotation::Rejected(StatusCode::UNAUTHORIZED)
+                            };
+
+                        match refresh_token_rotation {
+                            RefreshTokenRotation::Rotated(auth_session_response) => {
+                                refresh_token_rotated = true;
+                                response
+                                    .extensions_mut()
+                                    .insert(auth_session_response.access_token_response().clone());
+                                response
+                                    .extensions_mut()
+                                    .insert(auth_session_response.refresh_token_response().clone());
+                                cookie_jar
+                            }
+                            RefreshTokenRotation::ReuseDetected => {
+                                log::warn!("Reuse of a rotated refresh token detected");
+
+                                *response.status_mut() = StatusCode::UNAUTHORIZED;
+                                cookie_jar
+                                    .add(create_access_token_cookie(
+                                        &access_token_cookie_name,
+                                        "",
+                                        time::OffsetDateTime::UNIX_EPOCH,
+                                        access_token_cookie_path(
+                                            refresh_token_rotation_extension
+                                                .0
+                                                .access_token_path
+                                                .as_deref()
+                                                .unwrap_or(&access_token_path),
+                                        ),
+                                        access_token_same_site,
+                                        &config,
+                                    ))
+                                    .add(create_refresh_token_cookie(
+                                        &*refresh_token_cookie_name,
+                                        "",
+                                        time::OffsetDateTime::UNIX_EPOCH,
+                                        cookie_path(
+                                            &base_path,
+                                            refresh_token_rotation_extension
+                                                .0
+                                                .refresh_token_path
+                                                .as_deref()
+                                                .unwrap_or("/"),
+                                        ),
+                                        &config,
+                                    ))
+                            }
+                            RefreshTokenRotation::Rejected(status_code) => {
+                                *response.status_mut() = status_code;
+                                cookie_jar
+                            }
+                        }
+                    } else {
+                        cookie_jar
+                    };
+
+                    let access_token_response = remove_extension_if(
+                        response.extensions_mut(),
+                        |access_token_response: &AccessTokenResponse| {
+                            access_token_response
+                                .cookie_name()
+                                .is_none_or(|cookie_name| {
+                                    cookie_name == &*access_token_response_cookie_name
+                                })
+                        },
+                    );
                     let cookie_jar = if let Some(access_token_response) = &access_token_response {
-                        cookie_jar.add(create_access_token_cookie(
+                        // a __Host- prefixed cookie is set at / without a domain regardless
+                        if host_cookie_prefix
+                            && (access_token_response
+                                .0
+                                .path
+                                .as_deref()
+                                .is_some_and(|path| path != "/")
+                                || access_token_response.domain().is_some())
+                        {
+                            log::warn!(
+                                "The path and the domain of a __Host- prefixed access token cookie are ignored"
+                            );
+                        }
+
+                        let mut access_token_cookie = create_access_token_cookie(
+                            &access_token_cookie_name,
                             access_token_response.token().to_string(),
                             *access_token_response.expires_at(),
-                            access_token_response.path(),
-                        ))
+                            access_token_cookie_path(
+                                access_token_response
+                                    .0
+                                    .path
+                                    .as_deref()
+                                    .unwrap_or(&access_token_path),
+                            ),
+                            access_token_response.0.same_site.map_or(
+                                access_token_same_site,
+                                |same_site| {
+                                    access_token_cookie_same_site(
+                                        same_site,
+                                        lax_access_token_cookie,
+                                    )
+                                },
+                            ),
+                            &config,
+                        );
+                        if let (Some(domain), false) =
+                            (access_token_response.domain(), host_cookie_prefix)
+                        {
+                            access_token_cookie.set_domain(domain.to_string());
+                        }
+                        if access_token_response.is_session() {
+                            access_token_cookie.unset_expires();
+                        }
+                        cookie_jar.add(access_token_cookie)
                     } else {
                         cookie_jar
                     };
 
-                    let refresh_token_response =
-                        response.extensions_mut().remove::<RefreshTokenResponse>();
+                    if let (Some(audit), Some(access_token_response)) =
+                        (&audit, &access_token_response)
+                    {
+                        let login_info = match &received_access_token_login_result_pair {
+                            Some((_access_token, Ok(login_info))) => Some(&**login_info),
+                            _ => None,
+                        };
+                        if refresh_token_rotated {
+                            audit.on_refresh(access_token_response, login_info);
+                        } else {
+                            audit.on_login(access_token_response, login_info);
+                        }
+                    }
+
+                    let refresh_token_response = remove_extension_if(
+                        response.extensions_mut(),
+                        |refresh_token_response: &RefreshTokenResponse| {
+                            refresh_token_response
+                                .cookie_name()
+                                .is_none_or(|cookie_name| {
+                                    cookie_name == &*refresh_token_cookie_name
+                                })
+                        },
+                    );
                     let cookie_jar = if let Some(refresh_token_response) = &refresh_token_response {
                         cookie_jar.add(create_refresh_token_cookie(
+                            &*refresh_token_cookie_name,
                             refresh_token_response.token().to_string(),
                             *refresh_token_response.expires_at(),
-                            refresh_token_response.path(),
+                            cookie_path(&base_path, refresh_token_response.path()),
+                            &config,
                         ))
                     } else {
                         cookie_jar
                     };
 
+                    // a logout or an access token of the request handler discards the renewal
+                    // decision, an access token issued for it is never sent, so it is revoked
+                    let renewal_decision = match renewal_decision {
+                        Some(
+                            RenewalDecision::Renew(renewed_access_token, _)
+                            | RenewalDecision::RenewSession(renewed_access_token),
+                        ) if access_token_response.is_some()
+                            || response.extensions().get::<AuthLogoutExtension>().is_some() =>
+                        {
+                            if let Some((access_token, Ok(login_info))) =
+                                &received_access_token_login_result_pair
+                            {
+                                if renewed_access_token != *access_token {
+                                    auth_impl
+                                        .revoke_access_token(&renewed_access_token, login_info)
+                                        .await;
+                                }
+                            }
+                            None
+                        }
+                        renewal_decision => renewal_decision,
+                    };
+
                     let cookie_jar = if let Some(auth_logout_extension) =
                         response.extensions_mut().remove::<AuthLogoutExtension>()
                     {
@@ -252,51 +1491,142 @@ where
                             auth_impl
                                 .revoke_access_token(access_token, login_info)
                                 .await;
+
+                            if let (Some(audit), false) =
+                                (&audit, auth_logout_extension.0.keep_refresh_token)
+                            {
+                                audit.on_logout(login_info);
+                            }
+
+                            evict_from_verification_cache(&verification_cache, access_token);
                         }
 
-                        if let Some((refresh_token, Ok(()))) = &received_refresh_token {
+                        if let (Some((refresh_token, Ok(()))), false) = (
+                            &received_refresh_token,
+                            auth_logout_extension.0.keep_refresh_token,
+                        ) {
                             auth_impl.revoke_refresh_token(refresh_token).await;
                         }
 
-                        let cookie_jar = cookie_jar
-                            .add(create_access_token_cookie(
+                        // the cookie jar holds a single cookie per name, so the cookies of
+                        // the different paths are set directly as headers
+                        let access_token_paths = if host_cookie_prefix {
+                            vec!["/".to_string()]
+                        } else if let Some(access_token_paths) =
+                            &auth_logout_extension.0.access_token_paths
+                        {
+                            access_token_paths
+                                .iter()
+                                .map(|path| access_token_cookie_path(path))
+                                .collect()
+                        } else {
+                            vec![access_token_cookie_path(&access_token_path)]
+                        };
+                        let auth_logout_response = &auth_logout_extension.0;
+                        for path in &access_token_paths {
+                            let mut access_token_cookie = create_access_token_cookie(
+                                &access_token_cookie_name,
                                 "",
                                 time::OffsetDateTime::UNIX_EPOCH,
-                                auth_logout_extension
-                                    .0
-                                    .access_token_path
-                                    .as_deref()
-                                    .unwrap_or("/"),
-                            ))
-                            .add(create_access_token_cookie(
+                                path,
+                                auth_logout_response
+                                    .same_site
+                                    .unwrap_or(access_token_same_site),
+                                &config,
+                            );
+                            if let Some(secure) = auth_logout_response.secure {
+                                let same_site_none =
+                                    access_token_cookie.same_site() == Some(SameSite::None);
+                                access_token_cookie
+                                    .set_secure(secure || same_site_none || host_cookie_prefix);
+                            }
+                            if let (Some(domain), false) =
+                                (&auth_logout_response.domain, host_cookie_prefix)
+                            {
+                                access_token_cookie.set_domain(domain.clone());
+                            }
+                            append_set_cookie(response.headers_mut(), access_token_cookie);
+                        }
+                        for path in &auth_logout_response.refresh_token_paths {
+                            let mut refresh_token_cookie = create_refresh_token_cookie(
+                                &*refresh_token_cookie_name,
                                 "",
                                 time::OffsetDateTime::UNIX_EPOCH,
-                                auth_logout_extension
-                                    .0
-                                    .refresh_token_path
-                                    .as_deref()
-                                    .unwrap_or("/"),
-                            ));
+                                cookie_path(&base_path, path),
+                                &config,
+                            );
+                            if let (Some(same_site), false) =
+                                (auth_logout_response.same_site, partitioned)
+                            {
+                                refresh_token_cookie.set_same_site(same_site);
+                            }
+                            let same_site_none =
+                                refresh_token_cookie.same_site() == Some(SameSite::None);
+                            if same_site_none {
+                                refresh_token_cookie.set_secure(true);
+                            } else if let Some(secure) = auth_logout_response.secure {
+                                refresh_token_cookie.set_secure(secure);
+                            }
+                            if let Some(domain) = &auth_logout_response.domain {
+                                refresh_token_cookie.set_domain(domain.clone());
+                            }
+                            append_set_cookie(response.headers_mut(), refresh_token_cookie);
+                        }
 
                         cookie_jar
-                    } else if let Some((access_token, Ok(login_info))) =
-                        &received_access_token_login_result_pair
-                    {
-                        if access_token_response.is_none() {
-                            if let Some((access_token, expiration_time_delta)) = auth_impl
-                                .update_access_token(access_token, login_info)
-                                .await
-                            {
+                    } else if let Some(renewal_decision) = renewal_decision {
+                        match renewal_decision {
+                            RenewalDecision::Renew(access_token, expiration_time_delta) => {
                                 cookie_jar.add(create_access_token_cookie(
+                                    &access_token_cookie_name,
                                     access_token,
                                     time::OffsetDateTime::now_utc() + expiration_time_delta,
-                                    "/",
+                                    access_token_cookie_path(&access_token_path),
+                                    access_token_same_site,
+                                    &config,
                                 ))
-                            } else {
+                            }
+                            RenewalDecision::RenewSession(access_token) => {
+                                let mut access_token_cookie = create_access_token_cookie(
+                                    &access_token_cookie_name,
+                                    access_token,
+                                    time::OffsetDateTime::now_utc(),
+                                    access_token_cookie_path(&access_token_path),
+                                    access_token_same_site,
+                                    &config,
+                                );
+                                access_token_cookie.unset_expires();
+                                cookie_jar.add(access_token_cookie)
+                            }
+                            RenewalDecision::Keep => cookie_jar,
+                            RenewalDecision::Failed => {
+                                if let Some(header_name) = renewal_failure_header_name {
+                                    response
+                                        .headers_mut()
+                                        .insert(header_name, HeaderValue::from_static("failed"));
+                                }
+
                                 cookie_jar
                             }
-                        } else {
-                            cookie_jar
+                            RenewalDecision::Expire => {
+                                if let Some((access_token, _login_result)) =
+                                    &received_access_token_login_result_pair
+                                {
+                                    evict_from_verification_cache(
+                                        &verification_cache,
+                                        access_token,
+                                    );
+                                }
+
+                                cookie_jar.add(create_access_token_cookie(
+                                    &access_token_cookie_name,
+                                    "",
+                                    time::OffsetDateTime::UNIX_EPOCH,
+                                    access_token_cookie_path(&access_token_path),
+                                    access_token_same_site,
+                                    &config,
+                                ))
+                            }
                         }
                     } else {
                         cookie_jar
@@ -310,10 +1640,49 @@ where
                         ),
                     );
 
+                    if vary_cookie && access_token_from_cookie {
+                        append_vary_cookie(response.headers_mut());
+                    }
+
+                    if cookie_debug_header {
+                        append_cookie_debug_headers(
+                            response.headers_mut(),
+                            [&access_token_cookie_name, &refresh_token_cookie_name],
+                        );
+                    }
+
                     Ok(response)
                 }
                 Err(e) => Err(e),
             }
+        };
+
+        Box::pin(async move {
+            let mut response = response_future.await?;
+            if strict_cross_site_navigation && response.status() == StatusCode::UNAUTHORIZED {
+                static STRICT_COOKIE_HINT: Once = Once::new();
+                STRICT_COOKIE_HINT.call_once(|| {
+                    log::warn!(
+                        "A cross-site navigation was answered with 401, browsers do not send SameSite=Strict cookies on it, consider AuthLayer::with_lax_access_token_cookie"
+                    );
+                });
+            }
+            if let Some(login_path) = login_redirect {
+                if response.status() == StatusCode::UNAUTHORIZED {
+                    let mut redirect = Redirect::to(&login_path).into_response();
+                    for set_cookie in response.headers().get_all(SET_COOKIE) {
+                        redirect
+                            .headers_mut()
+                            .append(SET_COOKIE, set_cookie.clone());
+                    }
+                    response = redirect;
+                }
+            }
+            Ok(if let Some(accept) = negotiated_error_responses_accept {
+                negotiate_error_response(response, accept.as_ref())
+            } else {
+                response
+            })
         })
     }
 }
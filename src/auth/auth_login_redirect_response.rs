@@ -0,0 +1,58 @@
+use axum::response::{IntoResponse, Redirect, Response};
+use tokio::time::Duration;
+
+use super::{AccessToken, AccessTokenResponse, SafeRedirect};
+
+/// Sets the access token cookie and redirects with `303 See Other` to `location`,
+/// e.g., to the dashboard after a form-based login.
+#[derive(Clone)]
+pub struct AuthLoginRedirectResponse {
+    access_token_response: AccessTokenResponse,
+    location: String,
+}
+
+impl AuthLoginRedirectResponse {
+    pub fn new(
+        access_token: impl Into<AccessToken>,
+        expiration_time_delta: Duration,
+        location: impl Into<String>,
+    ) -> Self {
+        Self::with_access_token_response(
+            AccessTokenResponse::with_time_delta(access_token, expiration_time_delta, None),
+            location,
+        )
+    }
+
+    pub fn with_access_token_response(
+        access_token_response: AccessTokenResponse,
+        location: impl Into<String>,
+    ) -> Self {
+        Self {
+            access_token_response,
+            location: location.into(),
+        }
+    }
+
+    /// Same as `with_access_token_response` with a target validated by `SafeRedirect`, e.g.,
+    /// the `?next=` parameter of the login form.
+    pub fn with_safe_redirect(
+        access_token_response: AccessTokenResponse,
+        safe_redirect: SafeRedirect,
+    ) -> Self {
+        Self::with_access_token_response(access_token_response, safe_redirect.as_str())
+    }
+
+    pub fn access_token_response(&self) -> &AccessTokenResponse {
+        &self.access_token_response
+    }
+
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+}
+
+impl IntoResponse for AuthLoginRedirectResponse {
+    fn into_response(self) -> Response {
+        (self.access_token_response, Redirect::to(&self.location)).into_response()
+    }
+}
@@ -1,29 +1,127 @@
-use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use std::convert::Infallible;
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use axum_extra::extract::cookie::SameSite;
 
 use super::auth_layer::AuthLogoutExtension;
 
+const CLEAR_SITE_DATA_HEADER_NAME: HeaderName = HeaderName::from_static("clear-site-data");
+
 #[derive(Clone)]
 pub struct AuthLogoutResponse {
-    pub(super) access_token_path: Option<String>,
-    pub(super) refresh_token_path: Option<String>,
+    // the access token path of the auth layer if `None`
+    pub(super) access_token_paths: Option<Vec<String>>,
+    pub(super) refresh_token_paths: Vec<String>,
+    // the domain of the auth layer if `None`
+    pub(super) domain: Option<String>,
+    // the attributes of the auth layer if `None`
+    pub(super) same_site: Option<SameSite>,
+    pub(super) secure: Option<bool>,
+    // set by `AccessTokenExpireResponse`, which ends the access token only
+    pub(super) keep_refresh_token: bool,
+    clear_site_data: Option<HeaderValue>,
 }
 
 impl AuthLogoutResponse {
+    /// Clears the access token and the refresh token cookies at the given paths. The access token
+    /// cookie is cleared at the path of the auth layer if `None`, see
+    /// `AuthLayer::with_access_token_path`, the refresh token cookie at `/`.
     pub fn new(
         access_token_path: Option<impl Into<String>>,
         refresh_token_path: Option<impl Into<String>>,
     ) -> Self {
         Self {
-            access_token_path: access_token_path.map(|path| path.into()),
-            refresh_token_path: refresh_token_path.map(|path| path.into()),
+            access_token_paths: access_token_path.map(|path| vec![path.into()]),
+            refresh_token_paths: vec![refresh_token_path
+                .map(|path| path.into())
+                .unwrap_or_else(|| "/".to_string())],
+            domain: None,
+            same_site: None,
+            secure: None,
+            keep_refresh_token: false,
+            clear_site_data: None,
+        }
+    }
+
+    /// Clears the access token and the refresh token cookies at every given path.
+    /// Browsers store the cookies with the same name but different paths separately,
+    /// so every path the tokens were ever issued at has to be cleared.
+    pub fn with_paths(
+        access_token_paths: impl IntoIterator<Item = impl Into<String>>,
+        refresh_token_paths: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            access_token_paths: Some(
+                access_token_paths
+                    .into_iter()
+                    .map(|path| path.into())
+                    .collect(),
+            ),
+            refresh_token_paths: refresh_token_paths
+                .into_iter()
+                .map(|path| path.into())
+                .collect(),
+            domain: None,
+            same_site: None,
+            secure: None,
+            keep_refresh_token: false,
+            clear_site_data: None,
         }
     }
+
+    /// Sets the Domain attribute of the cleared cookies, by default it is the one of the auth
+    /// layer, see `AuthLayer::with_cookie_domain`. Browsers clear a cookie only if it is cleared
+    /// with the domain it was set with, e.g., an access token cookie set with
+    /// `AccessTokenResponse::with_domain`. The domain is not set on a `__Host-` prefixed cookie.
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the SameSite attribute of the cleared cookies, by default it is the one the auth layer
+    /// sets the cookies with, see `AuthLayer::with_access_token_same_site`.
+    /// Browsers may refuse to clear a cookie if the attributes do not match the ones it was set
+    /// with, e.g., an access token cookie set with `AccessTokenResponse::same_site`.
+    /// Partitioned cookies are cleared with `SameSite=None` regardless.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Sets the Secure attribute of the cleared cookies, by default it is the one the auth layer
+    /// sets the cookies with, see `AuthLayer::with_secure_cookies`.
+    /// `SameSite=None`, partitioned and `__Host-` prefixed cookies are cleared as `Secure`
+    /// regardless.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = Some(secure);
+        self
+    }
+
+    /// Sets the `Clear-Site-Data` header on the response with the given directives, e.g.,
+    /// `["cookies", "storage"]` makes the browser clear every cookie and the storage of the site,
+    /// not only the auth cookies. The header is not set by default.
+    pub fn clear_site_data<'a>(mut self, directives: impl IntoIterator<Item = &'a str>) -> Self {
+        let header_value = directives
+            .into_iter()
+            .map(|directive| format!("\"{directive}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.clear_site_data = HeaderValue::from_str(&header_value).ok();
+        self
+    }
 }
 
 impl IntoResponseParts for AuthLogoutResponse {
-    type Error = ();
+    type Error = Infallible;
 
-    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+    fn into_response_parts(mut self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Some(clear_site_data) = self.clear_site_data.take() {
+            res.headers_mut()
+                .insert(CLEAR_SITE_DATA_HEADER_NAME, clear_site_data);
+        }
         res.extensions_mut().insert(AuthLogoutExtension(self));
 
         Ok(res)
@@ -0,0 +1,48 @@
+use std::convert::Infallible;
+
+use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+
+use super::{AccessTokenResponse, RefreshTokenResponse};
+
+/// Sets both the access token and the refresh token cookies, e.g., as the response of a login.
+#[derive(Debug, Clone)]
+pub struct AuthSessionResponse {
+    access_token_response: AccessTokenResponse,
+    refresh_token_response: RefreshTokenResponse,
+}
+
+impl AuthSessionResponse {
+    pub fn new(
+        access_token_response: AccessTokenResponse,
+        refresh_token_response: RefreshTokenResponse,
+    ) -> Self {
+        Self {
+            access_token_response,
+            refresh_token_response,
+        }
+    }
+
+    pub fn access_token_response(&self) -> &AccessTokenResponse {
+        &self.access_token_response
+    }
+
+    pub fn refresh_token_response(&self) -> &RefreshTokenResponse {
+        &self.refresh_token_response
+    }
+}
+
+impl IntoResponseParts for AuthSessionResponse {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut().insert(self.access_token_response);
+        res.extensions_mut().insert(self.refresh_token_response);
+        Ok(res)
+    }
+}
+
+impl IntoResponse for AuthSessionResponse {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
@@ -0,0 +1,165 @@
+use std::{future::Future, marker::PhantomData, pin::Pin, sync::Arc};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::auth_layer::AccessTokenVerificationResultExtension;
+
+/// Role guard decides whether an authenticated user is allowed to access a route.
+pub trait RoleGuard<LoginInfoType>: Send + Sync + 'static {
+    fn authorize(login_info: &LoginInfoType) -> bool;
+
+    /// Login info used for requests without an access token, e.g., a guest user. If it is
+    /// `Some`, it is authorized the same way as an authenticated user instead of rejecting the
+    /// request with `401 Unauthorized`.
+    fn anonymous() -> Option<LoginInfoType> {
+        None
+    }
+}
+
+/// Extracts the login info of the authenticated user if the `GuardType` authorizes it.
+///
+/// Rejects with `401 Unauthorized` if the request is not authenticated and
+/// `GuardType::anonymous()` is `None`, and with `403 Forbidden` if the guard does not authorize
+/// the user.
+pub struct Authorized<LoginInfoType, GuardType>(pub Arc<LoginInfoType>, PhantomData<GuardType>)
+where
+    LoginInfoType: Send + Sync + 'static,
+    GuardType: RoleGuard<LoginInfoType>;
+
+impl<LoginInfoType, GuardType> Authorized<LoginInfoType, GuardType>
+where
+    LoginInfoType: Send + Sync + 'static,
+    GuardType: RoleGuard<LoginInfoType>,
+{
+    pub fn login_info(&self) -> &Arc<LoginInfoType> {
+        &self.0
+    }
+
+    pub fn into_login_info(self) -> Arc<LoginInfoType> {
+        self.0
+    }
+}
+
+impl<StateType, LoginInfoType, GuardType> FromRequestParts<StateType>
+    for Authorized<LoginInfoType, GuardType>
+where
+    LoginInfoType: Send + Sync + 'static,
+    GuardType: RoleGuard<LoginInfoType>,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let login_info = parts
+            .extensions
+            .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+            .map_or_else(
+                || {
+                    GuardType::anonymous()
+                        .map(Arc::new)
+                        .ok_or(StatusCode::UNAUTHORIZED)
+                },
+                |access_token_verification_result_extension| {
+                    access_token_verification_result_extension.0.clone()
+                },
+            )
+            .and_then(|login_info| {
+                if GuardType::authorize(&login_info) {
+                    Ok(Authorized(login_info, PhantomData))
+                } else {
+                    Err(StatusCode::FORBIDDEN)
+                }
+            });
+
+        Box::pin(async move { login_info })
+    }
+}
+
+/// Roles lists the roles that are allowed to access a route, and reads the role of a user.
+pub trait Roles<LoginInfoType>: Send + Sync + 'static {
+    const ALLOWED_ROLES: &'static [&'static str];
+
+    fn role_of(login_info: &LoginInfoType) -> &str;
+
+    /// Login info used for requests without an access token, e.g., a user with a guest role. If
+    /// it is `Some`, its role is checked the same way as the role of an authenticated user
+    /// instead of rejecting the request with `401 Unauthorized`.
+    fn anonymous() -> Option<LoginInfoType> {
+        None
+    }
+}
+
+/// Extracts the login info of the authenticated user if the role of the user is one of
+/// `RolesType::ALLOWED_ROLES`.
+///
+/// Rejects with `401 Unauthorized` if the request is not authenticated and
+/// `RolesType::anonymous()` is `None`, and with `403 Forbidden` if the role of the user is not
+/// allowed.
+pub struct RequireRoleIn<LoginInfoType, RolesType>(pub Arc<LoginInfoType>, PhantomData<RolesType>)
+where
+    LoginInfoType: Send + Sync + 'static,
+    RolesType: Roles<LoginInfoType>;
+
+impl<LoginInfoType, RolesType> RequireRoleIn<LoginInfoType, RolesType>
+where
+    LoginInfoType: Send + Sync + 'static,
+    RolesType: Roles<LoginInfoType>,
+{
+    pub fn login_info(&self) -> &Arc<LoginInfoType> {
+        &self.0
+    }
+
+    pub fn into_login_info(self) -> Arc<LoginInfoType> {
+        self.0
+    }
+}
+
+impl<StateType, LoginInfoType, RolesType> FromRequestParts<StateType>
+    for RequireRoleIn<LoginInfoType, RolesType>
+where
+    LoginInfoType: Send + Sync + 'static,
+    RolesType: Roles<LoginInfoType>,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let login_info = parts
+            .extensions
+            .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+            .map_or_else(
+                || {
+                    RolesType::anonymous()
+                        .map(Arc::new)
+                        .ok_or(StatusCode::UNAUTHORIZED)
+                },
+                |access_token_verification_result_extension| {
+                    access_token_verification_result_extension.0.clone()
+                },
+            )
+            .and_then(|login_info| {
+                let role = RolesType::role_of(&login_info);
+                if RolesType::ALLOWED_ROLES.contains(&role) {
+                    Ok(RequireRoleIn(login_info, PhantomData))
+                } else {
+                    Err(StatusCode::FORBIDDEN)
+                }
+            });
+
+        Box::pin(async move { login_info })
+    }
+}
@@ -0,0 +1,34 @@
+use std::{future::Future, pin::Pin};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::TokenSource;
+
+/// Extracts the raw token of the `Authorization: Bearer <token>` header, e.g., to pass it on to a
+/// downstream API. The token is not verified and `AuthLayer` does not have to be installed.
+///
+/// Rejects with `401 Unauthorized` if the header is missing or malformed.
+pub struct BearerToken(pub String);
+
+impl<StateType> FromRequestParts<StateType> for BearerToken {
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let bearer_token = TokenSource::BearerHeader
+            .access_token(parts)
+            .map(String::from)
+            .filter(|token| !token.is_empty())
+            .map(BearerToken)
+            .ok_or(StatusCode::UNAUTHORIZED);
+
+        Box::pin(async move { bearer_token })
+    }
+}
@@ -0,0 +1,125 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+
+use super::{AccessToken, AuthHandler, RefreshToken, RenewalDecision};
+
+/// Future returned by the closures of `FnAuthHandler`.
+pub type AuthFnFuture<OutputType> = Pin<Box<dyn Future<Output = OutputType> + Send>>;
+
+type VerifyAccessTokenFn<LoginInfoType> =
+    Arc<dyn Fn(AccessToken) -> AuthFnFuture<Result<LoginInfoType, StatusCode>> + Send + Sync>;
+type UpdateAccessTokenFn<LoginInfoType> =
+    Arc<dyn Fn(AccessToken, Arc<LoginInfoType>) -> AuthFnFuture<RenewalDecision> + Send + Sync>;
+type RevokeAccessTokenFn<LoginInfoType> =
+    Arc<dyn Fn(AccessToken, Arc<LoginInfoType>) -> AuthFnFuture<()> + Send + Sync>;
+type VerifyRefreshTokenFn =
+    Arc<dyn Fn(RefreshToken) -> AuthFnFuture<Result<(), StatusCode>> + Send + Sync>;
+type RevokeRefreshTokenFn = Arc<dyn Fn(RefreshToken) -> AuthFnFuture<()> + Send + Sync>;
+
+/// `AuthHandler` built from closures, like `tower::service_fn`, so small apps do not have to
+/// implement the trait on a struct. The closures receive clones of the tokens and return boxed
+/// futures, e.g., `|access_token| Box::pin(async move { ... })`.
+///
+/// Refresh tokens are rejected with `401 Unauthorized` unless `with_refresh_token_fns` is used.
+pub struct FnAuthHandler<LoginInfoType> {
+    verify_access_token_fn: VerifyAccessTokenFn<LoginInfoType>,
+    update_access_token_fn: UpdateAccessTokenFn<LoginInfoType>,
+    revoke_access_token_fn: RevokeAccessTokenFn<LoginInfoType>,
+    verify_refresh_token_fn: VerifyRefreshTokenFn,
+    revoke_refresh_token_fn: RevokeRefreshTokenFn,
+}
+
+impl<LoginInfoType> FnAuthHandler<LoginInfoType> {
+    pub fn new(
+        verify_access_token_fn: impl Fn(AccessToken) -> AuthFnFuture<Result<LoginInfoType, StatusCode>>
+            + Send
+            + Sync
+            + 'static,
+        update_access_token_fn: impl Fn(AccessToken, Arc<LoginInfoType>) -> AuthFnFuture<RenewalDecision>
+            + Send
+            + Sync
+            + 'static,
+        revoke_access_token_fn: impl Fn(AccessToken, Arc<LoginInfoType>) -> AuthFnFuture<()>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self {
+            verify_access_token_fn: Arc::new(verify_access_token_fn),
+            update_access_token_fn: Arc::new(update_access_token_fn),
+            revoke_access_token_fn: Arc::new(revoke_access_token_fn),
+            verify_refresh_token_fn: Arc::new(|_refresh_token| {
+                Box::pin(async { Err(StatusCode::UNAUTHORIZED) })
+            }),
+            revoke_refresh_token_fn: Arc::new(|_refresh_token| Box::pin(async {})),
+        }
+    }
+
+    /// Sets the closures verifying and revoking refresh tokens.
+    pub fn with_refresh_token_fns(
+        mut self,
+        verify_refresh_token_fn: impl Fn(RefreshToken) -> AuthFnFuture<Result<(), StatusCode>>
+            + Send
+            + Sync
+            + 'static,
+        revoke_refresh_token_fn: impl Fn(RefreshToken) -> AuthFnFuture<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.verify_refresh_token_fn = Arc::new(verify_refresh_token_fn);
+        self.revoke_refresh_token_fn = Arc::new(revoke_refresh_token_fn);
+        self
+    }
+}
+
+impl<LoginInfoType> Clone for FnAuthHandler<LoginInfoType> {
+    fn clone(&self) -> Self {
+        Self {
+            verify_access_token_fn: self.verify_access_token_fn.clone(),
+            update_access_token_fn: self.update_access_token_fn.clone(),
+            revoke_access_token_fn: self.revoke_access_token_fn.clone(),
+            verify_refresh_token_fn: self.verify_refresh_token_fn.clone(),
+            revoke_refresh_token_fn: self.revoke_refresh_token_fn.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<LoginInfoType> AuthHandler<LoginInfoType> for FnAuthHandler<LoginInfoType>
+where
+    LoginInfoType: Send + Sync + 'static,
+{
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfoType, StatusCode> {
+        (self.verify_access_token_fn)(access_token.clone()).await
+    }
+
+    async fn update_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        login_info: &Arc<LoginInfoType>,
+    ) -> RenewalDecision {
+        (self.update_access_token_fn)(access_token.clone(), login_info.clone()).await
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        login_info: &Arc<LoginInfoType>,
+    ) {
+        (self.revoke_access_token_fn)(access_token.clone(), login_info.clone()).await
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        (self.verify_refresh_token_fn)(refresh_token.clone()).await
+    }
+
+    async fn revoke_refresh_token(&mut self, refresh_token: &RefreshToken) {
+        (self.revoke_refresh_token_fn)(refresh_token.clone()).await
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{FromRequestParts, Request},
+    http::{header::CONTENT_TYPE, HeaderMap, Method, StatusCode},
+    response::{IntoResponse, Response},
+    BoxError,
+};
+use http_body_util::LengthLimitError;
+use ring::hmac;
+use tower::{Layer, Service};
+
+use super::{auth_layer::VerifiedAccessTokenExtension, AccessToken};
+
+const CSRF_FIELD_NAME: &str = "_csrf";
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+#[derive(Clone)]
+struct FormCsrfKeyExtension(hmac::Key);
+
+fn csrf_token(key: &hmac::Key, access_token: &AccessToken) -> String {
+    hmac::sign(key, access_token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn is_csrf_token_valid(key: &hmac::Key, access_token: &AccessToken, csrf_token: &str) -> bool {
+    if !csrf_token.len().is_multiple_of(2) || !csrf_token.is_ascii() {
+        return false;
+    }
+
+    let tag: Option<Vec<u8>> = (0..csrf_token.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&csrf_token[index..index + 2], 16).ok())
+        .collect();
+
+    tag.is_some_and(|tag| hmac::verify(key, access_token.as_bytes(), &tag).is_ok())
+}
+
+/// Tells whether a browser can send the request cross-site as a plain form submission, i.e., it
+/// is a `POST` with a form, multipart or plain text body, or without a `Content-Type`.
+fn is_form_post(method: &Method, headers: &HeaderMap) -> bool {
+    if method != Method::POST {
+        return false;
+    }
+
+    headers
+        .get(CONTENT_TYPE)
+        .map(|content_type| {
+            content_type
+                .to_str()
+                .ok()
+                .and_then(|content_type| content_type.split(';').next())
+                .map(|media_type| {
+                    let media_type = media_type.trim();
+                    [
+                        "application/x-www-form-urlencoded",
+                        "multipart/form-data",
+                        "text/plain",
+                    ]
+                    .iter()
+                    .any(|form_media_type| media_type.eq_ignore_ascii_case(form_media_type))
+                })
+                .unwrap_or(true)
+        })
+        .unwrap_or(true)
+}
+
+fn is_form_urlencoded(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .is_some_and(|media_type| {
+            media_type
+                .trim()
+                .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        })
+}
+
+/// Returns the value of the `_csrf` field of a form-urlencoded body, the CSRF token contains
+/// only hex digits, so it is never percent-encoded.
+fn csrf_field(body: &[u8]) -> Option<&str> {
+    std::str::from_utf8(body)
+        .ok()?
+        .split('&')
+        .find_map(|field| {
+            field
+                .split_once('=')
+                .filter(|(name, _value)| *name == CSRF_FIELD_NAME)
+                .map(|(_name, value)| value)
+        })
+}
+
+/// Extracts the CSRF token of the session, to be embedded in HTML forms as the `_csrf` field,
+/// which is validated by `FormCsrfLayer` on submit.
+///
+/// The token is an HMAC of the access token of the request with the key of `FormCsrfLayer`, so
+/// it is stable as long as the access token is and needs no server-side storage.
+/// Rejects with `401 Unauthorized` if the request carries no valid access token and with
+/// `500 Internal Server Error` if `FormCsrfLayer` is missing.
+pub struct FormCsrf(pub String);
+
+impl<StateType> FromRequestParts<StateType> for FormCsrf {
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let csrf_token = parts
+            .extensions
+            .get::<VerifiedAccessTokenExtension>()
+            .ok_or(StatusCode::UNAUTHORIZED)
+            .and_then(|verified_access_token_extension| {
+                parts
+                    .extensions
+                    .get::<FormCsrfKeyExtension>()
+                    .map(|form_csrf_key_extension| {
+                        FormCsrf(csrf_token(
+                            &form_csrf_key_extension.0,
+                            &verified_access_token_extension.0,
+                        ))
+                    })
+                    .ok_or(StatusCode::INTERNAL_SERVER_ERROR)
+            });
+
+        Box::pin(async move { csrf_token })
+    }
+}
+
+/// Validates the `_csrf` field of form submissions against the CSRF token of the session, see
+/// `FormCsrf`, and rejects the ones without a valid token with `403 Forbidden`.
+///
+/// Only authenticated `POST` requests a browser can send cross-site as a plain form submission
+/// are checked: form-urlencoded, multipart and plain text bodies and bodies without a
+/// `Content-Type`. Requests without a valid access token, e.g., the login form, are passed on.
+/// The token is read from form-urlencoded bodies only, the other checked ones are always
+/// rejected, so such routes need another CSRF protection. The checked bodies are buffered up to
+/// 2 MiB by default, see `with_body_limit`, larger ones are rejected with `413 Payload Too Large`.
+///
+/// It has to be added inside (before) `AuthLayer`, as it reads the access token verified by it.
+#[derive(Clone)]
+pub struct FormCsrfLayer {
+    key: hmac::Key,
+    body_limit: usize,
+}
+
+impl FormCsrfLayer {
+    /// Creates the layer with the server-side secret `key` the CSRF tokens are derived from,
+    /// it should be at least 32 random bytes.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, key.as_ref()),
+            body_limit: DEFAULT_BODY_LIMIT,
+        }
+    }
+
+    /// Sets the maximum size in bytes of the checked bodies, 2 MiB by default.
+    pub fn with_body_limit(mut self, body_limit: usize) -> Self {
+        self.body_limit = body_limit;
+        self
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for FormCsrfLayer {
+    type Service = FormCsrfMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        FormCsrfMiddleware {
+            inner,
+            key: self.key.clone(),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FormCsrfMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    key: hmac::Key,
+    body_limit: usize,
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for FormCsrfMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: http_body::Body<Data = Bytes> + Send + 'static,
+    RequestBodyType::Error: Into<BoxError>,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<RequestBodyType>) -> Self::Future {
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+
+        let key = self.key.clone();
+        let body_limit = self.body_limit;
+        req.extensions_mut()
+            .insert(FormCsrfKeyExtension(key.clone()));
+        let access_token = req
+            .extensions()
+            .get::<VerifiedAccessTokenExtension>()
+            .map(|verified_access_token_extension| verified_access_token_extension.0.clone())
+            .filter(|_access_token| is_form_post(req.method(), req.headers()));
+
+        Box::pin(async move {
+            let req = if let Some(access_token) = access_token {
+                let form_urlencoded = is_form_urlencoded(req.headers());
+                let (parts, body) = req.into_parts();
+                let body = match to_bytes(Body::new(body), body_limit).await {
+                    Ok(body) => body,
+                    Err(err) => {
+                        let status_code = if err.into_inner().is::<LengthLimitError>() {
+                            StatusCode::PAYLOAD_TOO_LARGE
+                        } else {
+                            StatusCode::BAD_REQUEST
+                        };
+                        return Ok(status_code.into_response());
+                    }
+                };
+
+                let csrf_token_valid = form_urlencoded
+                    && csrf_field(&body).is_some_and(|csrf_token| {
+                        is_csrf_token_valid(&key, &access_token, csrf_token)
+                    });
+                if !csrf_token_valid {
+                    return Ok(StatusCode::FORBIDDEN.into_response());
+                }
+
+                Request::from_parts(parts, Body::from(body))
+            } else {
+                req.map(Body::new)
+            };
+
+            inner
+                .call(req)
+                .await
+                .map(|next_response| next_response.into_response())
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::de::DeserializeOwned;
+
+use super::{AccessToken, AuthHandler, RefreshToken, RenewalDecision};
+
+/// Stateless `AuthHandler` for self-contained JWT access tokens, the login info is the claims
+/// decoded from the access token.
+///
+/// The access token is verified by its signature and by the given `Validation`, e.g., its
+/// expiration. As there is no token store, access tokens are never renewed and cannot be revoked,
+/// they stay valid until they expire. Refresh tokens are not supported, they are rejected with
+/// `401 Unauthorized`.
+pub struct JwtAuthHandler<ClaimsType> {
+    _marker: PhantomData<fn() -> ClaimsType>,
+
+    decoding_key: Arc<DecodingKey>,
+    validation: Arc<Validation>,
+}
+
+impl<ClaimsType> JwtAuthHandler<ClaimsType> {
+    pub fn new(decoding_key: DecodingKey, validation: Validation) -> Self {
+        Self {
+            _marker: PhantomData,
+
+            decoding_key: Arc::new(decoding_key),
+            validation: Arc::new(validation),
+        }
+    }
+}
+
+impl<ClaimsType> Clone for JwtAuthHandler<ClaimsType> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+
+            decoding_key: self.decoding_key.clone(),
+            validation: self.validation.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<ClaimsType> AuthHandler<ClaimsType> for JwtAuthHandler<ClaimsType>
+where
+    ClaimsType: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<ClaimsType, StatusCode> {
+        jsonwebtoken::decode::<ClaimsType>(access_token, &self.decoding_key, &self.validation)
+            .map(|token_data| token_data.claims)
+            .map_err(|e| {
+                log::debug!("JWT access token rejected, error = {}", e);
+                StatusCode::UNAUTHORIZED
+            })
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _claims: &Arc<ClaimsType>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _claims: &Arc<ClaimsType>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
@@ -1,14 +1,30 @@
-use std::{future::Future, pin::Pin, sync::Arc};
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
 
-use axum::{extract::FromRequestParts, http::StatusCode};
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+};
 
 use super::auth_layer::AccessTokenVerificationResultExtension;
 
-pub struct LoginInfoExtractor<LoginInfoType: Clone + Send + Sync + 'static>(pub Arc<LoginInfoType>);
+/// Login info of the request if the auth layer verified its access token successfully, e.g., to
+/// key a middleware running inside the auth layer by the user.
+pub fn login_info_from_parts<LoginInfoType: Send + Sync + 'static>(
+    parts: &Parts,
+) -> Option<Arc<LoginInfoType>> {
+    parts
+        .extensions
+        .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+        .and_then(|access_token_verification_result_extension| {
+            access_token_verification_result_extension.0.clone().ok()
+        })
+}
+
+pub struct LoginInfoExtractor<LoginInfoType: Send + Sync + 'static>(pub Arc<LoginInfoType>);
 
 impl<StateType, LoginInfoType> FromRequestParts<StateType> for LoginInfoExtractor<LoginInfoType>
 where
-    LoginInfoType: Clone + Send + Sync + 'static,
+    LoginInfoType: Send + Sync + 'static,
 {
     type Rejection = StatusCode;
 
@@ -37,3 +53,76 @@ where
         Box::pin(async move { login_info })
     }
 }
+
+/// Same as `LoginInfoExtractor`, but clones the login info out of the `Arc`, e.g., to move it into a spawned task.
+pub struct LoginInfo<LoginInfoType: Clone + Send + Sync + 'static>(pub LoginInfoType);
+
+impl<StateType, LoginInfoType> FromRequestParts<StateType> for LoginInfo<LoginInfoType>
+where
+    LoginInfoType: Clone + Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let login_info = parts
+            .extensions
+            .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+            .ok_or(StatusCode::UNAUTHORIZED)
+            .and_then(|access_token_verification_result_extension| {
+                Ok(LoginInfo(
+                    access_token_verification_result_extension
+                        .0
+                        .as_ref()?
+                        .as_ref()
+                        .clone(),
+                ))
+            });
+
+        Box::pin(async move { login_info })
+    }
+}
+
+/// The result of the access token verification of the request, without rejecting the request,
+/// e.g., to log why the access token of a hybrid page was rejected.
+///
+/// It is `None` if the request carried no access token (or the auth layer is missing), and the
+/// status code of the failed verification if the access token was rejected. `LoginStatus` holds
+/// the same information as an enum.
+pub struct LoginInfoExtractorWithError<LoginInfoType: Send + Sync + 'static>(
+    pub Option<Result<Arc<LoginInfoType>, StatusCode>>,
+);
+
+impl<StateType, LoginInfoType> FromRequestParts<StateType>
+    for LoginInfoExtractorWithError<LoginInfoType>
+where
+    LoginInfoType: Send + Sync + 'static,
+{
+    type Rejection = Infallible;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let verification_result = parts
+            .extensions
+            .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+            .map(|access_token_verification_result_extension| {
+                access_token_verification_result_extension.0.clone()
+            });
+
+        Box::pin(async move { Ok(LoginInfoExtractorWithError(verification_result)) })
+    }
+}
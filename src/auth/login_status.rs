@@ -0,0 +1,57 @@
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::auth_layer::AccessTokenVerificationResultExtension;
+
+/// Authentication state of a request, unlike `Option<LoginInfoExtractor>` it distinguishes
+/// requests without an access token from requests whose access token was rejected.
+pub enum LoginStatus<LoginInfoType: Send + Sync + 'static> {
+    /// The request did not contain an access token.
+    Anonymous,
+    /// The request contained an access token, but its verification failed.
+    Invalid(StatusCode),
+    /// The request contained a valid access token.
+    Authenticated(Arc<LoginInfoType>),
+}
+
+impl<LoginInfoType: Send + Sync + 'static> LoginStatus<LoginInfoType> {
+    pub fn login_info(&self) -> Option<&Arc<LoginInfoType>> {
+        match self {
+            Self::Authenticated(login_info) => Some(login_info),
+            _ => None,
+        }
+    }
+}
+
+impl<StateType, LoginInfoType> FromRequestParts<StateType> for LoginStatus<LoginInfoType>
+where
+    LoginInfoType: Send + Sync + 'static,
+{
+    type Rejection = Infallible;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let login_status = match parts
+            .extensions
+            .get::<AccessTokenVerificationResultExtension<LoginInfoType>>()
+        {
+            Some(AccessTokenVerificationResultExtension(Ok(login_info))) => {
+                LoginStatus::Authenticated(login_info.clone())
+            }
+            Some(AccessTokenVerificationResultExtension(Err(status_code))) => {
+                LoginStatus::Invalid(*status_code)
+            }
+            None => LoginStatus::Anonymous,
+        };
+
+        Box::pin(async move { Ok(login_status) })
+    }
+}
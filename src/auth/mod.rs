@@ -1,16 +1,72 @@
+mod access_token_expire_response;
 mod access_token_response;
+mod auth_audit;
+mod auth_config;
+mod auth_context;
+mod auth_error;
 mod auth_handler;
 mod auth_layer;
+mod auth_login_redirect_response;
 mod auth_logout_response;
+mod auth_session_response;
+mod authorized;
+mod bearer_token;
+mod fn_auth_handler;
+#[cfg(feature = "form-csrf")]
+mod form_csrf;
+#[cfg(feature = "jwt")]
+mod jwt_auth_handler;
 mod login_info_extractor;
+mod login_status;
+mod public_route;
+mod recently_authenticated;
 mod refresh_token_extractor;
 mod refresh_token_response;
+mod refresh_token_rotation;
+mod renewed_this_request;
+mod require_fresh_access_token;
+mod safe_redirect;
+mod session_control;
+mod tenant_extractor;
+mod token_generator;
 mod token_response;
+mod token_source;
+mod verification_cache;
 
+pub use access_token_expire_response::AccessTokenExpireResponse;
 pub use access_token_response::AccessTokenResponse;
-pub use auth_handler::{AccessToken, AuthHandler, RefreshToken};
-pub use auth_layer::AuthLayer;
+pub use auth_audit::AuthAudit;
+pub use auth_config::AuthConfig;
+pub use auth_context::{AuthContext, AuthContextExtractor};
+pub use auth_error::AuthError;
+pub use auth_handler::{AccessToken, AuthHandler, RefreshToken, RenewalDecision};
+pub use auth_layer::{is_cookie_expired_at, is_cookie_expired_by_date, AuthLayer, AuthMiddleware};
+pub use auth_login_redirect_response::AuthLoginRedirectResponse;
 pub use auth_logout_response::AuthLogoutResponse;
-pub use login_info_extractor::LoginInfoExtractor;
-pub use refresh_token_extractor::RefreshTokenExtractor;
+pub use auth_session_response::AuthSessionResponse;
+pub use authorized::{Authorized, RequireRoleIn, RoleGuard, Roles};
+pub use axum_extra::extract::cookie::{Cookie, SameSite};
+pub use bearer_token::BearerToken;
+pub use fn_auth_handler::{AuthFnFuture, FnAuthHandler};
+#[cfg(feature = "form-csrf")]
+pub use form_csrf::{FormCsrf, FormCsrfLayer, FormCsrfMiddleware};
+#[cfg(feature = "jwt")]
+pub use jwt_auth_handler::JwtAuthHandler;
+pub use login_info_extractor::{
+    login_info_from_parts, LoginInfo, LoginInfoExtractor, LoginInfoExtractorWithError,
+};
+pub use login_status::LoginStatus;
+pub use public_route::PublicRoute;
+pub use recently_authenticated::RecentlyAuthenticated;
+pub use refresh_token_extractor::{
+    RefreshTokenExtractor, RefreshTokenExtractorWithRejection, RefreshTokenRejection,
+};
 pub use refresh_token_response::RefreshTokenResponse;
+pub use refresh_token_rotation::{RefreshTokenRotation, RefreshTokenRotationResponse};
+pub use renewed_this_request::RenewedThisRequest;
+pub use require_fresh_access_token::RequireFreshAccessToken;
+pub use safe_redirect::SafeRedirect;
+pub use session_control::SessionControl;
+pub use tenant_extractor::TenantExtractor;
+pub use token_generator::{SecureRandomTokenGenerator, TokenGenerator, UuidTokenGenerator};
+pub use token_source::TokenSource;
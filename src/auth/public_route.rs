@@ -0,0 +1,11 @@
+/// Marks a request as public, `AuthMiddleware` passes it to the inner service untouched like the
+/// requests matched by `AuthLayer::with_public_path_matcher`, its tokens are not verified and no
+/// cookies are set or cleared in its response.
+///
+/// It is inserted with the `axum::Extension(PublicRoute)` layer, which has to run before the
+/// auth middleware, i.e., it has to wrap the auth layer, e.g.,
+/// `get(handler).route_layer(auth_layer).layer(Extension(PublicRoute))`. Note that a layer added
+/// to a route of a router runs after the layers added to the router with `Router::route_layer`
+/// or `Router::layer`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PublicRoute;
@@ -0,0 +1,41 @@
+use std::{future::Future, pin::Pin};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+use time::{Duration, OffsetDateTime};
+
+use super::auth_layer::AuthenticatedAtExtension;
+
+/// Extracts when the session of the request was authenticated, if it was within the last `SECS`
+/// seconds, e.g., to require a recent login for changing the email address or deleting the
+/// account (step-up authentication).
+///
+/// The time of the authentication is returned by `AuthHandler::authenticated_at`. Rejects with
+/// `401 Unauthorized` if the request carries no valid access token, if the auth handler does not
+/// know when its session was authenticated, or if it was authenticated longer than `SECS` seconds
+/// ago.
+pub struct RecentlyAuthenticated<const SECS: u64>(pub OffsetDateTime);
+
+impl<StateType, const SECS: u64> FromRequestParts<StateType> for RecentlyAuthenticated<SECS> {
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let max_age = Duration::seconds(i64::try_from(SECS).unwrap_or(i64::MAX));
+        let authenticated_at = parts
+            .extensions
+            .get::<AuthenticatedAtExtension>()
+            .map(|authenticated_at_extension| authenticated_at_extension.0)
+            .filter(|authenticated_at| OffsetDateTime::now_utc() - *authenticated_at <= max_age)
+            .map(RecentlyAuthenticated)
+            .ok_or(StatusCode::UNAUTHORIZED);
+
+        Box::pin(async move { authenticated_at })
+    }
+}
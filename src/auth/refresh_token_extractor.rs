@@ -1,9 +1,39 @@
-use std::{future::Future, pin::Pin};
+use std::{future::Future, marker::PhantomData, pin::Pin};
 
-use axum::{extract::FromRequestParts, http::StatusCode};
+use axum::{extract::FromRequestParts, http::StatusCode, response::IntoResponse};
 
 use super::{auth_layer::RefreshTokenVerificationResultExtension, RefreshToken};
 
+/// Rejection of the refresh token extractors, built from the status code describing why the
+/// refresh token is missing or invalid.
+pub trait RefreshTokenRejection: IntoResponse + Send + 'static {
+    fn from_status_code(status_code: StatusCode) -> Self;
+}
+
+impl RefreshTokenRejection for StatusCode {
+    fn from_status_code(status_code: StatusCode) -> Self {
+        status_code
+    }
+}
+
+fn extract_refresh_token(parts: &axum::http::request::Parts) -> Result<RefreshToken, StatusCode> {
+    parts
+        .extensions
+        .get::<RefreshTokenVerificationResultExtension>()
+        .ok_or(StatusCode::UNAUTHORIZED)
+        .and_then(|refresh_token_verification_result_extension| {
+            if let Err(status_code) = refresh_token_verification_result_extension.0 .1 {
+                Err(status_code)
+            } else {
+                Ok(refresh_token_verification_result_extension.0 .0.clone())
+            }
+        })
+}
+
+/// Extracts the refresh token of the request if it was verified successfully.
+///
+/// Rejects with `401 Unauthorized` if the request carries no refresh token, and with the status
+/// code returned by `AuthHandler::verify_refresh_token` if the verification failed.
 pub struct RefreshTokenExtractor(pub RefreshToken);
 
 impl<StateType> FromRequestParts<StateType> for RefreshTokenExtractor {
@@ -18,19 +48,48 @@ impl<StateType> FromRequestParts<StateType> for RefreshTokenExtractor {
         'life1: 'async_trait,
         Self: 'async_trait,
     {
-        let refresh_token = parts
-            .extensions
-            .get::<RefreshTokenVerificationResultExtension>()
-            .ok_or(StatusCode::UNAUTHORIZED)
-            .and_then(|refresh_token_verification_result_extension| {
-                if let Err(status_code) = refresh_token_verification_result_extension.0 .1 {
-                    Err(status_code)
-                } else {
-                    Ok(RefreshTokenExtractor(
-                        refresh_token_verification_result_extension.0 .0.clone(),
-                    ))
-                }
-            });
+        let refresh_token = extract_refresh_token(parts).map(RefreshTokenExtractor);
+
+        Box::pin(async move { refresh_token })
+    }
+}
+
+/// Same as `RefreshTokenExtractor`, but rejects with `RejectionType`, e.g., to redirect the
+/// browser to the login page when the refresh token is missing or invalid.
+pub struct RefreshTokenExtractorWithRejection<RejectionType: RefreshTokenRejection>(
+    pub RefreshToken,
+    PhantomData<RejectionType>,
+);
+
+impl<RejectionType: RefreshTokenRejection> RefreshTokenExtractorWithRejection<RejectionType> {
+    pub fn refresh_token(&self) -> &RefreshToken {
+        &self.0
+    }
+
+    pub fn into_refresh_token(self) -> RefreshToken {
+        self.0
+    }
+}
+
+impl<StateType, RejectionType> FromRequestParts<StateType>
+    for RefreshTokenExtractorWithRejection<RejectionType>
+where
+    RejectionType: RefreshTokenRejection,
+{
+    type Rejection = RejectionType;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let refresh_token = extract_refresh_token(parts)
+            .map(|refresh_token| RefreshTokenExtractorWithRejection(refresh_token, PhantomData))
+            .map_err(RejectionType::from_status_code);
 
         Box::pin(async move { refresh_token })
     }
@@ -4,7 +4,9 @@ use axum::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
 use time::OffsetDateTime;
 use tokio::time::Duration;
 
-use super::{token_response::TokenResponse, RefreshToken};
+use super::{
+    token_response::TokenResponse, RefreshToken, SecureRandomTokenGenerator, TokenGenerator,
+};
 
 #[derive(Debug, Clone)]
 pub struct RefreshTokenResponse(pub(super) TokenResponse<RefreshToken>);
@@ -34,6 +36,22 @@ impl RefreshTokenResponse {
         ))
     }
 
+    /// Creates a response with a new refresh token generated by `SecureRandomTokenGenerator`.
+    pub fn generate(expiration_time_delta: Duration, path: &str) -> Self {
+        Self::with_time_delta(
+            SecureRandomTokenGenerator::default().generate_token(),
+            expiration_time_delta,
+            path,
+        )
+    }
+
+    /// Targets the auth layer whose refresh token cookie has the given name, see
+    /// `AuthLayer::with_cookie_names`. The innermost auth layer sets the cookie by default.
+    pub fn with_cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.0.cookie_name = Some(cookie_name.into());
+        self
+    }
+
     pub fn token(&self) -> &RefreshToken {
         &self.0.token
     }
@@ -42,8 +60,17 @@ impl RefreshTokenResponse {
         &self.0.expires_at
     }
 
+    /// Time left until the token expires, zero if it is already expired.
+    pub fn expires_in(&self) -> Duration {
+        self.0.expires_in()
+    }
+
     pub fn path(&self) -> &str {
-        &self.0.path
+        self.0.path.as_deref().unwrap_or("/")
+    }
+
+    pub fn cookie_name(&self) -> Option<&str> {
+        self.0.cookie_name.as_deref()
     }
 }
 
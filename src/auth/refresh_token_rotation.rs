@@ -0,0 +1,60 @@
+use std::convert::Infallible;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+
+use super::{auth_layer::RefreshTokenRotationExtension, AuthSessionResponse};
+
+/// Outcome of `AuthHandler::rotate_refresh_token`.
+// it is returned once per rotation, boxing the session response would only burden the handlers
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum RefreshTokenRotation {
+    /// The refresh token was replaced, the new access token and refresh token are sent to the client.
+    Rotated(AuthSessionResponse),
+    /// The refresh token had already been rotated, so it is likely stolen. The handler is expected
+    /// to revoke the whole token family, the auth layer clears the cookies and responds with `401 Unauthorized`.
+    ReuseDetected,
+    /// The refresh token could not be rotated, the auth layer responds with the given status code.
+    Rejected(StatusCode),
+}
+
+/// Instructs the auth layer to rotate the refresh token of the request by calling
+/// `AuthHandler::rotate_refresh_token`.
+#[derive(Clone)]
+pub struct RefreshTokenRotationResponse {
+    pub(super) access_token_path: Option<String>,
+    pub(super) refresh_token_path: Option<String>,
+}
+
+impl RefreshTokenRotationResponse {
+    /// The paths are used to clear the cookies when a reuse of the refresh token is detected.
+    pub fn new(
+        access_token_path: Option<impl Into<String>>,
+        refresh_token_path: Option<impl Into<String>>,
+    ) -> Self {
+        Self {
+            access_token_path: access_token_path.map(|path| path.into()),
+            refresh_token_path: refresh_token_path.map(|path| path.into()),
+        }
+    }
+}
+
+impl IntoResponseParts for RefreshTokenRotationResponse {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.extensions_mut()
+            .insert(RefreshTokenRotationExtension(self));
+
+        Ok(res)
+    }
+}
+
+impl IntoResponse for RefreshTokenRotationResponse {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
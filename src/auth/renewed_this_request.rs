@@ -0,0 +1,35 @@
+use std::{convert::Infallible, future::Future, pin::Pin};
+
+use axum::extract::FromRequestParts;
+
+use super::auth_layer::AccessTokenRenewedExtension;
+
+/// Tells whether the auth layer renews the access token cookie in the response of this request.
+///
+/// The renewal is decided before the request handler runs. It is `false` for requests without a
+/// valid access token and for requests where `update_access_token` did not return
+/// `RenewalDecision::Renew`. The renewal is discarded if the handler responds with its own
+/// `AccessTokenResponse` or with an `AuthLogoutResponse`.
+pub struct RenewedThisRequest(pub bool);
+
+impl<StateType> FromRequestParts<StateType> for RenewedThisRequest {
+    type Rejection = Infallible;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let renewed = parts
+            .extensions
+            .get::<AccessTokenRenewedExtension>()
+            .map(|access_token_renewed_extension| access_token_renewed_extension.0)
+            .unwrap_or(false);
+
+        Box::pin(async move { Ok(RenewedThisRequest(renewed)) })
+    }
+}
@@ -0,0 +1,39 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::LoginInfoExtractor;
+
+/// Same as `LoginInfoExtractor`, named for the handlers that must be reached with a valid access
+/// token, e.g., a password change, to make it explicit at the handler that a valid refresh token
+/// does not substitute the access token.
+///
+/// The request is rejected with `401 Unauthorized` if it carries no access token, or with the
+/// status code of the failed verification if the access token is invalid.
+pub struct RequireFreshAccessToken<LoginInfoType: Send + Sync + 'static>(pub Arc<LoginInfoType>);
+
+impl<StateType, LoginInfoType> FromRequestParts<StateType>
+    for RequireFreshAccessToken<LoginInfoType>
+where
+    LoginInfoType: Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let login_info = LoginInfoExtractor::<LoginInfoType>::from_request_parts(parts, state);
+
+        Box::pin(async move {
+            login_info
+                .await
+                .map(|LoginInfoExtractor(login_info)| RequireFreshAccessToken(login_info))
+        })
+    }
+}
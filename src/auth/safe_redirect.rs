@@ -0,0 +1,57 @@
+use axum::response::{IntoResponse, Redirect, Response};
+
+/// A redirect target validated against an allowlist of path prefixes, e.g., the `?next=`
+/// parameter of a login form, so it cannot be abused as an open redirect.
+///
+/// Responds with `303 See Other` to the target, so it can be combined with the token responses,
+/// e.g., `(AuthLogoutResponse::new(None, None), safe_redirect)`, see also
+/// `AuthLoginRedirectResponse::with_safe_redirect`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SafeRedirect(String);
+
+impl SafeRedirect {
+    /// Returns `next` if it is a local path starting with one of `allowed_prefixes`, at a path
+    /// segment boundary, e.g., `/app` allows `/app` and `/app/settings?tab=1`, but not
+    /// `/application`.
+    ///
+    /// Absolute URLs (`https://evil.com`), protocol-relative URLs (`//evil.com`), targets with
+    /// backslashes, which browsers treat as `/`, e.g., `/\evil.com` or `/app/..\admin`, targets
+    /// with whitespace or control characters and targets with `.` or `..` path segments are
+    /// rejected.
+    pub fn validate(next: &str, allowed_prefixes: &[&str]) -> Option<String> {
+        Self::new(next, allowed_prefixes).map(|safe_redirect| safe_redirect.0)
+    }
+
+    /// Same as `validate`, but returns the validated target as a `SafeRedirect`.
+    pub fn new(next: &str, allowed_prefixes: &[&str]) -> Option<Self> {
+        let path = next.split(['?', '#']).next().unwrap_or_default();
+
+        // browsers treat backslashes as slashes, e.g., `/\evil.com` is `//evil.com`
+        let local = next.starts_with('/') && !next.starts_with("//") && !next.contains('\\');
+        // browsers strip tabs and newlines from URLs, e.g., `/\t/evil.com` is `//evil.com`
+        let printable = !next
+            .chars()
+            .any(|character| character.is_whitespace() || character.is_control());
+        let normalized = !path.split('/').any(|segment| {
+            let segment = segment.to_ascii_lowercase().replace("%2e", ".");
+            segment == "." || segment == ".."
+        });
+        let allowed = allowed_prefixes.iter().any(|allowed_prefix| {
+            path.strip_prefix(allowed_prefix).is_some_and(|rest| {
+                rest.is_empty() || allowed_prefix.ends_with('/') || rest.starts_with('/')
+            })
+        });
+
+        (local && printable && normalized && allowed).then(|| Self(next.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl IntoResponse for SafeRedirect {
+    fn into_response(self) -> Response {
+        Redirect::to(&self.0).into_response()
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::{auth_layer::SessionControlExtension, AuthLogoutResponse};
+
+/// Lets a request handler end the session without responding with an `AuthLogoutResponse`,
+/// e.g., deep in the business logic of a handler responding with JSON.
+///
+/// After the handler returned, the auth layer handles a logout requested by `logout` or
+/// `logout_with` exactly as if the handler had responded with the `AuthLogoutResponse`: the
+/// tokens are revoked and the cookies are cleared. An `AuthLogoutResponse` returned by the handler
+/// takes precedence over the requested one.
+/// Rejects with `500 Internal Server Error` if the auth layer is missing.
+#[derive(Clone)]
+pub struct SessionControl(Arc<Mutex<Option<AuthLogoutResponse>>>);
+
+impl SessionControl {
+    /// Requests a logout clearing the cookies at `/`, like `AuthLogoutResponse::new(None, None)`.
+    pub fn logout(&self) {
+        self.logout_with(AuthLogoutResponse::new(None::<String>, None::<String>));
+    }
+
+    /// Requests a logout with the given `AuthLogoutResponse`, e.g., to clear the cookies at
+    /// other paths, replacing the previously requested one.
+    pub fn logout_with(&self, auth_logout_response: AuthLogoutResponse) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(auth_logout_response);
+    }
+
+    pub fn is_logout_requested(&self) -> bool {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+}
+
+impl<StateType> FromRequestParts<StateType> for SessionControl {
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let session_control = parts
+            .extensions
+            .get::<SessionControlExtension>()
+            .map(|session_control_extension| SessionControl(session_control_extension.0.clone()))
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        Box::pin(async move { session_control })
+    }
+}
@@ -0,0 +1,37 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use axum::{extract::FromRequestParts, http::StatusCode};
+
+use super::auth_layer::TenantExtension;
+
+/// Extracts the tenant projected out of the login info by the auth layer, see
+/// `AuthLayer::with_tenant`.
+///
+/// Rejects with `401 Unauthorized` if the request is not authenticated, or if the auth layer does
+/// not project a tenant of type `TenantType`.
+pub struct TenantExtractor<TenantType: Send + Sync + 'static>(pub Arc<TenantType>);
+
+impl<StateType, TenantType> FromRequestParts<StateType> for TenantExtractor<TenantType>
+where
+    TenantType: Send + Sync + 'static,
+{
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let tenant = parts
+            .extensions
+            .get::<TenantExtension<TenantType>>()
+            .map(|tenant_extension| TenantExtractor(tenant_extension.0.clone()))
+            .ok_or(StatusCode::UNAUTHORIZED);
+
+        Box::pin(async move { tenant })
+    }
+}
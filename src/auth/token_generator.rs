@@ -0,0 +1,64 @@
+use uuid::Uuid;
+
+/// Generates the values of new access tokens and refresh tokens.
+pub trait TokenGenerator {
+    fn generate_token(&self) -> String;
+}
+
+/// Generates random (v4) UUIDs in their hyphenated form, with 122 bits of entropy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidTokenGenerator;
+
+impl TokenGenerator for UuidTokenGenerator {
+    fn generate_token(&self) -> String {
+        Uuid::new_v4().as_hyphenated().to_string()
+    }
+}
+
+/// Generates `entropy_bytes` random bytes with the random number generator of the operating
+/// system, encoded as URL-safe base64 without padding. 32 bytes by default.
+#[derive(Debug, Clone, Copy)]
+pub struct SecureRandomTokenGenerator {
+    entropy_bytes: usize,
+}
+
+impl SecureRandomTokenGenerator {
+    pub fn new(entropy_bytes: usize) -> Self {
+        Self { entropy_bytes }
+    }
+}
+
+impl Default for SecureRandomTokenGenerator {
+    fn default() -> Self {
+        Self::new(32)
+    }
+}
+
+impl TokenGenerator for SecureRandomTokenGenerator {
+    fn generate_token(&self) -> String {
+        let mut bytes = vec![0; self.entropy_bytes];
+        getrandom::getrandom(&mut bytes)
+            .expect("the random number generator of the operating system is not available");
+
+        encode_base64_url_safe(&bytes)
+    }
+}
+
+fn encode_base64_url_safe(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+
+        // a chunk of n bytes is encoded into n + 1 characters, as there is no padding
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+
+    encoded
+}
@@ -1,3 +1,4 @@
+use axum_extra::extract::cookie::SameSite;
 use time::OffsetDateTime;
 use tokio::time::Duration;
 
@@ -5,7 +6,13 @@ use tokio::time::Duration;
 pub(super) struct TokenResponse<TokenType> {
     pub(super) token: TokenType,
     pub(super) expires_at: OffsetDateTime,
-    pub(super) path: String,
+    // the path of the auth layer if `None`
+    pub(super) path: Option<String>,
+    // the SameSite attribute of the auth layer if `None`
+    pub(super) same_site: Option<SameSite>,
+    pub(super) domain: Option<String>,
+    pub(super) cookie_name: Option<String>,
+    pub(super) session: bool,
 }
 
 impl<TokenType> TokenResponse<TokenType> {
@@ -17,9 +24,11 @@ impl<TokenType> TokenResponse<TokenType> {
         Self {
             token: token.into(),
             expires_at,
-            path: path
-                .map(|path| path.into())
-                .unwrap_or_else(|| "/".to_string()),
+            path: path.map(|path| path.into()),
+            same_site: None,
+            domain: None,
+            cookie_name: None,
+            session: false,
         }
     }
 
@@ -34,4 +43,10 @@ impl<TokenType> TokenResponse<TokenType> {
             path,
         )
     }
+
+    pub(super) fn expires_in(&self) -> Duration {
+        (self.expires_at - OffsetDateTime::now_utc())
+            .try_into()
+            .unwrap_or(Duration::ZERO)
+    }
 }
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::Query,
+    http::{header::AUTHORIZATION, request::Parts, HeaderName},
+};
+
+use super::AccessToken;
+
+/// A place of the request the auth layer reads the access token from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TokenSource {
+    /// The `access_token` cookie. Only access tokens received in the cookie are renewed.
+    Cookie,
+    /// The `Authorization: Bearer <token>` header.
+    BearerHeader,
+    /// The value of the given header.
+    Header(HeaderName),
+    /// The given query parameter.
+    /// Query parameters tend to end up in logs, so the access token may leak.
+    Query(String),
+}
+
+impl TokenSource {
+    /// Reads the access token from the request. The `Cookie` source is handled by the auth
+    /// layer itself, as it may carry several access tokens.
+    pub(super) fn access_token(&self, parts: &Parts) -> Option<AccessToken> {
+        match self {
+            TokenSource::Cookie => None,
+            TokenSource::BearerHeader => parts
+                .headers
+                .get(AUTHORIZATION)
+                .and_then(|header_value| header_value.to_str().ok())
+                .and_then(|header_value| header_value.split_once(' '))
+                .filter(|(scheme, _token)| scheme.eq_ignore_ascii_case("bearer"))
+                .map(|(_scheme, token)| AccessToken(token.trim().to_string())),
+            TokenSource::Header(header_name) => parts
+                .headers
+                .get(header_name)
+                .and_then(|header_value| header_value.to_str().ok())
+                .map(|header_value| AccessToken(header_value.to_string())),
+            TokenSource::Query(name) => Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+                .ok()
+                .and_then(|Query(mut query)| query.remove(name))
+                .map(AccessToken),
+        }
+    }
+}
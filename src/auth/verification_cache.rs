@@ -0,0 +1,87 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use super::AccessToken;
+
+struct VerificationCacheEntry<LoginInfoType> {
+    login_info: Arc<LoginInfoType>,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+/// LRU cache of successful access token verifications, entries expire after `ttl`.
+pub(super) struct VerificationCache<LoginInfoType> {
+    capacity: usize,
+    ttl: Duration,
+
+    entries: HashMap<AccessToken, VerificationCacheEntry<LoginInfoType>>,
+    access_tokens_by_last_used: BTreeMap<u64, AccessToken>,
+    clock: u64,
+}
+
+impl<LoginInfoType> VerificationCache<LoginInfoType> {
+    pub(super) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+
+            entries: HashMap::new(),
+            access_tokens_by_last_used: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    pub(super) fn get(&mut self, access_token: &AccessToken) -> Option<Arc<LoginInfoType>> {
+        let entry = self.entries.get_mut(access_token)?;
+
+        if entry.inserted_at.elapsed() >= self.ttl {
+            self.remove(access_token);
+            return None;
+        }
+
+        self.clock += 1;
+        self.access_tokens_by_last_used.remove(&entry.last_used);
+        self.access_tokens_by_last_used
+            .insert(self.clock, access_token.clone());
+        entry.last_used = self.clock;
+
+        Some(entry.login_info.clone())
+    }
+
+    pub(super) fn insert(&mut self, access_token: AccessToken, login_info: Arc<LoginInfoType>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.remove(&access_token);
+
+        while self.entries.len() >= self.capacity {
+            if let Some((_last_used, access_token)) = self.access_tokens_by_last_used.pop_first() {
+                self.entries.remove(&access_token);
+            } else {
+                break;
+            }
+        }
+
+        self.clock += 1;
+        self.access_tokens_by_last_used
+            .insert(self.clock, access_token.clone());
+        self.entries.insert(
+            access_token,
+            VerificationCacheEntry {
+                login_info,
+                inserted_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    pub(super) fn remove(&mut self, access_token: &AccessToken) {
+        if let Some(entry) = self.entries.remove(access_token) {
+            self.access_tokens_by_last_used.remove(&entry.last_used);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{header::CONTENT_LENGTH, StatusCode},
+    response::{IntoResponse, Response},
+};
+use http_body::Body;
+use http_body_util::Limited;
+use tower::{Layer, Service};
+
+/// Rejects requests whose body is larger than `max_bytes` with `413 Payload Too Large`.
+///
+/// Requests with a too large `Content-Length` are rejected before the inner service is called.
+/// Bodies without a `Content-Length` are counted while they are read, extractors that read
+/// past the limit reject the request with `413 Payload Too Large`.
+#[derive(Clone)]
+pub struct BodyLimitLayer {
+    max_bytes: usize,
+}
+
+impl BodyLimitLayer {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes }
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for BodyLimitLayer {
+    type Service = BodyLimitMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        BodyLimitMiddleware {
+            inner,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BodyLimitMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    max_bytes: usize,
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for BodyLimitMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<Limited<RequestBodyType>>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Body + Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        let content_length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|content_length| content_length.to_str().ok())
+            .and_then(|content_length| content_length.parse::<u64>().ok());
+
+        if let Some(content_length) = content_length {
+            if content_length > self.max_bytes as u64 {
+                return Box::pin(async move { Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response()) });
+            }
+        }
+
+        let max_bytes = self.max_bytes;
+        let req = req.map(|body| Limited::new(body, max_bytes));
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => Ok(next_response.into_response()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
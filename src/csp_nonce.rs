@@ -0,0 +1,167 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header::CONTENT_SECURITY_POLICY, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use http_body::Body;
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+/// Generates a random nonce for every request, and adds it to the `script-src` directive of the
+/// `Content-Security-Policy` response header as `'nonce-<nonce>'`.
+///
+/// The header set by the request handler is patched, otherwise `policy` is sent.
+/// Request handlers read the nonce with the `CspNonce` extractor, e.g., to render it into the
+/// `nonce` attribute of the script tags.
+#[derive(Clone)]
+pub struct CspNonceLayer {
+    policy: Arc<str>,
+}
+
+impl CspNonceLayer {
+    pub fn new(policy: impl AsRef<str>) -> Self {
+        Self {
+            policy: policy.as_ref().into(),
+        }
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for CspNonceLayer {
+    type Service = CspNonceMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        CspNonceMiddleware {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CspNonceMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    policy: Arc<str>,
+}
+
+/// Adds the nonce source to the `script-src` directive, the directive is added if it is missing.
+fn add_nonce_to_policy(policy: &str, nonce: &str) -> String {
+    let nonce_source = format!("'nonce-{nonce}'");
+
+    let mut has_script_src = false;
+    let mut directives = policy
+        .split(';')
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| {
+            let directive_name = directive.split_whitespace().next().unwrap_or_default();
+            if directive_name.eq_ignore_ascii_case("script-src") {
+                has_script_src = true;
+                format!("{directive} {nonce_source}")
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !has_script_src {
+        directives.push(format!("script-src {nonce_source}"));
+    }
+
+    directives.join("; ")
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for CspNonceMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Body + Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<RequestBodyType>) -> Self::Future {
+        let nonce = Uuid::new_v4().simple().to_string();
+        req.extensions_mut().insert(CspNonce(nonce.clone()));
+
+        let policy = self.policy.clone();
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => {
+                    let mut response = next_response.into_response();
+
+                    let policy = response
+                        .headers()
+                        .get(CONTENT_SECURITY_POLICY)
+                        .and_then(|header_value| header_value.to_str().ok())
+                        .map(|header_value| add_nonce_to_policy(header_value, &nonce))
+                        .unwrap_or_else(|| add_nonce_to_policy(&policy, &nonce));
+
+                    match HeaderValue::from_str(&policy) {
+                        Ok(header_value) => {
+                            response
+                                .headers_mut()
+                                .insert(CONTENT_SECURITY_POLICY, header_value);
+                        }
+                        Err(e) => {
+                            log::error!("Invalid Content-Security-Policy, error = {e}");
+                            return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+                        }
+                    }
+
+                    Ok(response)
+                }
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
+
+/// Extracts the nonce generated by `CspNonceLayer` for the request.
+///
+/// Rejects with `500 Internal Server Error` if the route is not wrapped by `CspNonceLayer`.
+#[derive(Debug, Clone)]
+pub struct CspNonce(pub String);
+
+impl<StateType> FromRequestParts<StateType> for CspNonce {
+    type Rejection = StatusCode;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 StateType,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        let csp_nonce = parts
+            .extensions
+            .get::<CspNonce>()
+            .cloned()
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        Box::pin(async move { csp_nonce })
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::HeaderName,
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::{
+    body_limit::{BodyLimitLayer, BodyLimitMiddleware},
+    require_content_type::{RequireContentTypeLayer, RequireContentTypeMiddleware},
+    response_timing::{ResponseTimingLayer, ResponseTimingMiddleware},
+};
+
+/// Header the response time is written into when `timing` is enabled without a header name.
+pub const DEFAULT_RESPONSE_TIME_HEADER_NAME: HeaderName =
+    HeaderName::from_static("x-response-time-ms");
+
+/// Builds a single layer out of the layers of this crate, every layer is disabled by default.
+///
+/// The layers are applied in a fixed order, from the outermost to the innermost:
+/// `ResponseTimingLayer`, `RequireContentTypeLayer`, `BodyLimitLayer`.
+/// So the response time includes the time of the rejections of the other layers.
+#[derive(Clone, Default)]
+pub struct HelperStack {
+    timing: Option<HeaderName>,
+    require_content_type: Option<String>,
+    body_limit: Option<usize>,
+}
+
+impl HelperStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables `ResponseTimingLayer` with `DEFAULT_RESPONSE_TIME_HEADER_NAME`.
+    pub fn timing(self) -> Self {
+        self.timing_with_header_name(DEFAULT_RESPONSE_TIME_HEADER_NAME)
+    }
+
+    /// Enables `ResponseTimingLayer` with the given header name.
+    pub fn timing_with_header_name(mut self, header_name: HeaderName) -> Self {
+        self.timing = Some(header_name);
+        self
+    }
+
+    /// Enables `RequireContentTypeLayer` with the given media type.
+    pub fn require_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.require_content_type = Some(content_type.into());
+        self
+    }
+
+    /// Enables `BodyLimitLayer` with the given limit.
+    pub fn body_limit(mut self, max_bytes: usize) -> Self {
+        self.body_limit = Some(max_bytes);
+        self
+    }
+
+    pub fn build(self) -> HelperStackLayer {
+        HelperStackLayer {
+            timing: self.timing.map(ResponseTimingLayer::new),
+            require_content_type: self.require_content_type.map(RequireContentTypeLayer::new),
+            body_limit: self.body_limit.map(BodyLimitLayer::new),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HelperStackLayer {
+    timing: Option<ResponseTimingLayer>,
+    require_content_type: Option<RequireContentTypeLayer>,
+    body_limit: Option<BodyLimitLayer>,
+}
+
+type BodyLimitStage<InnerServiceType> =
+    OptionalMiddleware<InnerServiceType, BodyLimitMiddleware<InnerServiceType>>;
+type RequireContentTypeStage<InnerServiceType> = OptionalMiddleware<
+    BodyLimitStage<InnerServiceType>,
+    RequireContentTypeMiddleware<BodyLimitStage<InnerServiceType>>,
+>;
+type ResponseTimingStage<InnerServiceType> = OptionalMiddleware<
+    RequireContentTypeStage<InnerServiceType>,
+    ResponseTimingMiddleware<RequireContentTypeStage<InnerServiceType>>,
+>;
+
+impl<InnerServiceType> Layer<InnerServiceType> for HelperStackLayer {
+    type Service = ResponseTimingStage<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        let inner = OptionalMiddleware::new(self.body_limit.as_ref(), inner);
+        let inner = OptionalMiddleware::new(self.require_content_type.as_ref(), inner);
+        OptionalMiddleware::new(self.timing.as_ref(), inner)
+    }
+}
+
+/// Either a middleware of a layer enabled in `HelperStack`, or the inner service itself if the
+/// layer is disabled.
+#[derive(Clone)]
+pub enum OptionalMiddleware<InnerServiceType, MiddlewareType> {
+    Enabled(MiddlewareType),
+    Disabled(InnerServiceType),
+}
+
+impl<InnerServiceType, MiddlewareType> OptionalMiddleware<InnerServiceType, MiddlewareType> {
+    fn new<LayerType>(layer: Option<&LayerType>, inner: InnerServiceType) -> Self
+    where
+        LayerType: Layer<InnerServiceType, Service = MiddlewareType>,
+    {
+        match layer {
+            Some(layer) => Self::Enabled(layer.layer(inner)),
+            None => Self::Disabled(inner),
+        }
+    }
+}
+
+impl<InnerServiceType, MiddlewareType, RequestBodyType> Service<Request<RequestBodyType>>
+    for OptionalMiddleware<InnerServiceType, MiddlewareType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>>,
+    InnerServiceType::Future: Send + 'static,
+    InnerServiceType::Response: IntoResponse,
+    MiddlewareType: Service<Request<RequestBodyType>, Error = InnerServiceType::Error>,
+    MiddlewareType::Future: Send + 'static,
+    MiddlewareType::Response: IntoResponse,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            Self::Enabled(middleware) => middleware.poll_ready(cx),
+            Self::Disabled(inner) => inner.poll_ready(cx),
+        }
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        match self {
+            Self::Enabled(middleware) => {
+                let future = middleware.call(req);
+                Box::pin(async move { future.await.map(IntoResponse::into_response) })
+            }
+            Self::Disabled(inner) => {
+                let future = inner.call(req);
+                Box::pin(async move { future.await.map(IntoResponse::into_response) })
+            }
+        }
+    }
+}
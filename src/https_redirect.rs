@@ -0,0 +1,151 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{header::HOST, HeaderName, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+use tower::{Layer, Service};
+
+/// Header the TLS terminating proxy writes the protocol of the request into by default.
+pub const DEFAULT_FORWARDED_PROTO_HEADER_NAME: HeaderName =
+    HeaderName::from_static("x-forwarded-proto");
+
+#[derive(Clone, Copy)]
+enum PlaintextHandling {
+    Redirect,
+    Reject(StatusCode),
+}
+
+/// Enforces HTTPS behind a TLS terminating proxy, based on the protocol the proxy forwards in the
+/// `X-Forwarded-Proto` header (or in the header set by `with_header_name`).
+///
+/// Plaintext requests are redirected with `308 Permanent Redirect` to the `https://` equivalent
+/// built from the `Host` header, requests without a `Host` header are rejected with
+/// `400 Bad Request`. `with_rejection` rejects plaintext requests instead.
+/// Requests without the forwarded protocol header are passed through.
+#[derive(Clone)]
+pub struct HttpsRedirectLayer {
+    header_name: HeaderName,
+    plaintext_handling: PlaintextHandling,
+}
+
+impl HttpsRedirectLayer {
+    pub fn new() -> Self {
+        Self {
+            header_name: DEFAULT_FORWARDED_PROTO_HEADER_NAME,
+            plaintext_handling: PlaintextHandling::Redirect,
+        }
+    }
+
+    /// Reads the forwarded protocol from the given header.
+    pub fn with_header_name(mut self, header_name: HeaderName) -> Self {
+        self.header_name = header_name;
+        self
+    }
+
+    /// Rejects plaintext requests with the given status code instead of redirecting them.
+    pub fn with_rejection(mut self, status_code: StatusCode) -> Self {
+        self.plaintext_handling = PlaintextHandling::Reject(status_code);
+        self
+    }
+}
+
+impl Default for HttpsRedirectLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for HttpsRedirectLayer {
+    type Service = HttpsRedirectMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        HttpsRedirectMiddleware {
+            inner,
+            header_name: self.header_name.clone(),
+            plaintext_handling: self.plaintext_handling,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpsRedirectMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    header_name: HeaderName,
+    plaintext_handling: PlaintextHandling,
+}
+
+/// The first value is the protocol of the client, proxies append the protocol of later hops.
+fn is_plaintext<RequestBodyType>(req: &Request<RequestBodyType>, header_name: &HeaderName) -> bool {
+    req.headers()
+        .get(header_name)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|header_value| header_value.split(',').next())
+        .map(|proto| proto.trim().eq_ignore_ascii_case("http"))
+        .unwrap_or(false)
+}
+
+fn https_redirect<RequestBodyType>(req: &Request<RequestBodyType>) -> Response {
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|header_value| header_value.to_str().ok());
+    match host {
+        Some(host) => {
+            let path_and_query = req
+                .uri()
+                .path_and_query()
+                .map(|path_and_query| path_and_query.as_str())
+                .unwrap_or("/");
+            Redirect::permanent(&format!("https://{host}{path_and_query}")).into_response()
+        }
+        None => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for HttpsRedirectMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        if is_plaintext(&req, &self.header_name) {
+            let response = match self.plaintext_handling {
+                PlaintextHandling::Redirect => https_redirect(&req),
+                PlaintextHandling::Reject(status_code) => status_code.into_response(),
+            };
+            return Box::pin(async move { Ok(response) });
+        }
+
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => Ok(next_response.into_response()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -1,6 +1,17 @@
 pub mod app;
 pub mod auth;
+pub mod body_limit;
+pub mod csp_nonce;
+pub mod helper_stack;
+pub mod https_redirect;
+pub mod normalize_path;
+pub mod rate_limit;
+pub mod require_content_type;
 pub mod response_http_header_mutator;
+pub mod response_timing;
+pub mod security_headers;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 #[cfg(test)]
 mod tests;
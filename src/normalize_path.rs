@@ -0,0 +1,114 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{uri::PathAndQuery, Uri},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+/// How `NormalizePathLayer` normalizes the trailing slash of the request path.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TrailingSlash {
+    /// `/account/` is served as `/account`.
+    Trim,
+    /// `/account` is served as `/account/`.
+    Append,
+}
+
+/// Normalizes the trailing slash of the request path, so e.g., `/account` and `/account/` are
+/// routed to the same handler. The root path `/` is left as it is.
+///
+/// The path has to be normalized before routing, so the layer has to wrap the `Router` itself,
+/// e.g., `NormalizePathLayer::new(TrailingSlash::Trim).layer(router)`, a layer added with
+/// `Router::layer` or `Router::route_layer` runs after routing.
+#[derive(Clone)]
+pub struct NormalizePathLayer {
+    trailing_slash: TrailingSlash,
+}
+
+impl NormalizePathLayer {
+    pub fn new(trailing_slash: TrailingSlash) -> Self {
+        Self { trailing_slash }
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for NormalizePathLayer {
+    type Service = NormalizePathMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        NormalizePathMiddleware {
+            inner,
+            trailing_slash: self.trailing_slash,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NormalizePathMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    trailing_slash: TrailingSlash,
+}
+
+fn normalize_path(uri: &Uri, trailing_slash: TrailingSlash) -> Option<Uri> {
+    let path = uri.path();
+    let trimmed_path = path.trim_end_matches('/');
+    let normalized_path = match trailing_slash {
+        TrailingSlash::Trim if trimmed_path.is_empty() => "/".to_string(),
+        TrailingSlash::Trim => trimmed_path.to_string(),
+        TrailingSlash::Append => format!("{trimmed_path}/"),
+    };
+    if normalized_path == path {
+        return None;
+    }
+
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{normalized_path}?{query}"),
+        None => normalized_path,
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for NormalizePathMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<RequestBodyType>) -> Self::Future {
+        if let Some(uri) = normalize_path(req.uri(), self.trailing_slash) {
+            *req.uri_mut() = uri;
+        }
+
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => Ok(next_response.into_response()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    http::{header::RETRY_AFTER, request::Parts, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+const MIN_PRUNE_AT_LEN: usize = 1024;
+
+type KeyFn<KeyType> = Arc<dyn Fn(&Parts) -> KeyType + Send + Sync>;
+
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token buckets of the keys, every bucket holds `capacity` tokens and it is refilled at
+/// `capacity` tokens per `period`.
+struct TokenBuckets<KeyType> {
+    capacity: f64,
+    period: Duration,
+    buckets: HashMap<KeyType, TokenBucket>,
+    prune_at_len: usize,
+}
+
+impl<KeyType: Hash + Eq> TokenBuckets<KeyType> {
+    /// Takes a token of the key, or returns the time until the next token is available.
+    fn take(&mut self, key: KeyType, now: Instant) -> Result<(), Duration> {
+        let capacity = self.capacity;
+        let tokens_per_second = capacity / self.period.as_secs_f64();
+
+        // full buckets carry no information, so they are dropped to bound the memory, the
+        // threshold grows with the number of active keys to keep the pruning amortized
+        if self.buckets.len() >= self.prune_at_len {
+            self.buckets.retain(|_key, bucket| {
+                let elapsed = now.saturating_duration_since(bucket.refilled_at);
+                bucket.tokens + elapsed.as_secs_f64() * tokens_per_second < capacity
+            });
+            self.prune_at_len = (self.buckets.len() * 2).max(MIN_PRUNE_AT_LEN);
+        }
+
+        let bucket = self.buckets.entry(key).or_insert(TokenBucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * tokens_per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            // without refill, i.e., with zero `max_requests`, the wait is infinite (or NaN with
+            // a zero `period` as well), so it saturates
+            Err(
+                Duration::try_from_secs_f64((1.0 - bucket.tokens) / tokens_per_second)
+                    .unwrap_or(Duration::MAX),
+            )
+        }
+    }
+}
+
+/// Limits the requests to `max_requests` per `period` per key, the key of a request is returned by
+/// `key_fn`, e.g., the user of the login info or the peer address of unauthenticated requests.
+/// Requests over the limit are rejected with `429 Too Many Requests` and a `Retry-After` header.
+///
+/// The limit is a token bucket, so bursts of `max_requests` requests are allowed.
+/// With zero `max_requests` every request is rejected, with a zero `period` none is.
+/// To key the requests by the login info (see `auth::login_info_from_parts`) the layer has to run
+/// inside the auth layer, i.e., it has to be added before the `AuthLayer`.
+pub struct RateLimitLayer<KeyType> {
+    key_fn: KeyFn<KeyType>,
+    token_buckets: Arc<Mutex<TokenBuckets<KeyType>>>,
+}
+
+impl<KeyType> RateLimitLayer<KeyType> {
+    pub fn new(
+        max_requests: u32,
+        period: Duration,
+        key_fn: impl Fn(&Parts) -> KeyType + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            key_fn: Arc::new(key_fn),
+            token_buckets: Arc::new(Mutex::new(TokenBuckets {
+                capacity: f64::from(max_requests),
+                period,
+                buckets: HashMap::new(),
+                prune_at_len: MIN_PRUNE_AT_LEN,
+            })),
+        }
+    }
+}
+
+impl<KeyType> Clone for RateLimitLayer<KeyType> {
+    fn clone(&self) -> Self {
+        Self {
+            key_fn: self.key_fn.clone(),
+            token_buckets: self.token_buckets.clone(),
+        }
+    }
+}
+
+impl<InnerServiceType, KeyType> Layer<InnerServiceType> for RateLimitLayer<KeyType> {
+    type Service = RateLimitMiddleware<InnerServiceType, KeyType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        RateLimitMiddleware {
+            inner,
+            key_fn: self.key_fn.clone(),
+            token_buckets: self.token_buckets.clone(),
+        }
+    }
+}
+
+pub struct RateLimitMiddleware<InnerServiceType, KeyType> {
+    inner: InnerServiceType,
+    key_fn: KeyFn<KeyType>,
+    token_buckets: Arc<Mutex<TokenBuckets<KeyType>>>,
+}
+
+impl<InnerServiceType: Clone, KeyType> Clone for RateLimitMiddleware<InnerServiceType, KeyType> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            key_fn: self.key_fn.clone(),
+            token_buckets: self.token_buckets.clone(),
+        }
+    }
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType, KeyType>
+    Service<Request<RequestBodyType>> for RateLimitMiddleware<InnerServiceType, KeyType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Send + 'static,
+    KeyType: Hash + Eq,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        let (parts, body) = req.into_parts();
+        let key = (self.key_fn)(&parts);
+        let taken = self
+            .token_buckets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take(key, Instant::now());
+
+        if let Err(retry_after) = taken {
+            // Retry-After is in whole seconds, rounded up so the retry is not rejected again
+            let retry_after_secs = retry_after
+                .as_secs()
+                .saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+            let response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(RETRY_AFTER, HeaderValue::from(retry_after_secs))],
+            )
+                .into_response();
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let req = Request::from_parts(parts, body);
+
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => Ok(next_response.into_response()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING},
+        HeaderMap, Method, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use http_body::Body;
+use tower::{Layer, Service};
+
+/// Rejects requests with `415 Unsupported Media Type` if their `Content-Type` is missing or does
+/// not match the given media type, parameters (e.g., `charset`) are ignored.
+///
+/// Only requests with a body are checked, i.e., `POST`, `PUT` and `PATCH` requests and requests
+/// with a non-zero `Content-Length` or a `Transfer-Encoding`. Requests with `Content-Length: 0`
+/// and without a `Transfer-Encoding` have no body, whatever their method.
+#[derive(Clone)]
+pub struct RequireContentTypeLayer {
+    content_type: Arc<str>,
+}
+
+impl RequireContentTypeLayer {
+    pub fn new(content_type: impl AsRef<str>) -> Self {
+        Self {
+            content_type: content_type.as_ref().into(),
+        }
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for RequireContentTypeLayer {
+    type Service = RequireContentTypeMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        RequireContentTypeMiddleware {
+            inner,
+            content_type: self.content_type.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RequireContentTypeMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    content_type: Arc<str>,
+}
+
+fn has_body(method: &Method, headers: &HeaderMap) -> bool {
+    if headers.contains_key(TRANSFER_ENCODING) {
+        return true;
+    }
+
+    let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|content_length| content_length.to_str().ok())
+        .and_then(|content_length| content_length.parse::<u64>().ok());
+
+    match content_length {
+        Some(content_length) => content_length > 0,
+        None => matches!(*method, Method::POST | Method::PUT | Method::PATCH),
+    }
+}
+
+fn is_content_type_matching(headers: &HeaderMap, content_type: &str) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|header_value| header_value.split(';').next())
+        .map(|media_type| media_type.trim().eq_ignore_ascii_case(content_type))
+        .unwrap_or(false)
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for RequireContentTypeMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Body + Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        if has_body(req.method(), req.headers())
+            && !is_content_type_matching(req.headers(), &self.content_type)
+        {
+            return Box::pin(async move { Ok(StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response()) });
+        }
+
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => Ok(next_response.into_response()),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -1,42 +1,103 @@
 use std::{
+    error::Error,
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 
 use axum::{
+    body::Bytes,
     extract::Request,
-    http::HeaderMap,
+    http::{HeaderMap, Method, StatusCode},
     response::{IntoResponse, Response},
+    BoxError,
 };
-use http_body::Body;
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::LengthLimitError;
 use tower::{Layer, Service};
 
-#[derive(Clone)]
+/// Calls `callback` with the request method, the request headers and the response headers, so it
+/// can mutate the latter, e.g., to set caching headers on the responses of `GET` requests only.
+/// Only the headers are touched, the response body is passed on as it is, so streaming responses
+/// (e.g., server-sent events) are not buffered.
+///
+/// If reading the request body fails, e.g., the client disconnects in the middle of an upload,
+/// the callback is not called, as the response was produced from an incomplete request, and a
+/// successful response of the inner service is replaced by `400 Bad Request`. Error responses
+/// are passed on as they are. Bodies exceeding a limit, e.g., of `BodyLimitLayer`, are not
+/// treated as failed, the inner service responds to them, e.g., with `413 Payload Too Large`.
 pub struct ResponseHttpHeaderMutatorLayer<
     CallbackErrorType: IntoResponse + Send + Sync + 'static,
-    CallbackType: Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync + 'static,
+    CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+        + Send
+        + Sync
+        + 'static,
 > {
     callback: Arc<CallbackType>,
 }
 
+// the callback is shared, so it does not have to be `Clone`
 impl<
         CallbackErrorType: IntoResponse + Send + Sync + 'static,
-        CallbackType: Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync + 'static,
+        CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+            + Send
+            + Sync
+            + 'static,
+    > Clone for ResponseHttpHeaderMutatorLayer<CallbackErrorType, CallbackType>
+{
+    fn clone(&self) -> Self {
+        Self {
+            callback: self.callback.clone(),
+        }
+    }
+}
+
+impl<
+        CallbackErrorType: IntoResponse + Send + Sync + 'static,
+        CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+            + Send
+            + Sync
+            + 'static,
     > ResponseHttpHeaderMutatorLayer<CallbackErrorType, CallbackType>
 {
-    pub fn new(callback: CallbackType) -> Self {
+    pub fn new_with_method(callback: CallbackType) -> Self {
         Self {
             callback: Arc::new(callback),
         }
     }
 }
 
+/// Callback of a `ResponseHttpHeaderMutatorLayer` created by `new`.
+pub type HeaderMutatorCallback<CallbackErrorType> =
+    Box<dyn Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync>;
+
+impl<CallbackErrorType: IntoResponse + Send + Sync + 'static>
+    ResponseHttpHeaderMutatorLayer<CallbackErrorType, HeaderMutatorCallback<CallbackErrorType>>
+{
+    /// Same as `new_with_method`, but `callback` does not receive the request method.
+    pub fn new(
+        callback: impl Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        Self::new_with_method(Box::new(move |_method, req_headers, res_headers| {
+            callback(req_headers, res_headers)
+        }))
+    }
+}
+
 impl<
         InnerServiceType,
         CallbackErrorType: IntoResponse + Send + Sync + 'static,
-        CallbackType: Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync + 'static,
+        CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+            + Send
+            + Sync
+            + 'static,
     > Layer<InnerServiceType> for ResponseHttpHeaderMutatorLayer<CallbackErrorType, CallbackType>
 {
     type Service =
@@ -50,29 +111,53 @@ impl<
     }
 }
 
-#[derive(Clone)]
 pub struct ResponseHttpHeaderMutatorMiddleware<
     InnerServiceType,
     CallbackErrorType: IntoResponse + Send + Sync + 'static,
-    CallbackType: Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync + 'static,
+    CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+        + Send
+        + Sync
+        + 'static,
 > {
     inner: InnerServiceType,
     callback: Arc<CallbackType>,
 }
 
+// the callback is shared, so it does not have to be `Clone`
+impl<
+        InnerServiceType: Clone,
+        CallbackErrorType: IntoResponse + Send + Sync + 'static,
+        CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+            + Send
+            + Sync
+            + 'static,
+    > Clone
+    for ResponseHttpHeaderMutatorMiddleware<InnerServiceType, CallbackErrorType, CallbackType>
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            callback: self.callback.clone(),
+        }
+    }
+}
+
 impl<InnerServiceType, RequestBodyType, InnerResponseType, CallbackErrorType, CallbackType>
     Service<Request<RequestBodyType>>
     for ResponseHttpHeaderMutatorMiddleware<InnerServiceType, CallbackErrorType, CallbackType>
 where
     CallbackErrorType: IntoResponse + Send + Sync + 'static,
-    CallbackType:
-        Fn(&HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType> + Send + Sync + 'static,
-    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    CallbackType: Fn(&Method, &HeaderMap, &mut HeaderMap) -> Result<(), CallbackErrorType>
+        + Send
+        + Sync
+        + 'static,
+    InnerServiceType: Service<Request> + Clone + Send + 'static,
     InnerServiceType::Future:
         Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
     InnerServiceType::Error: Send,
     InnerResponseType: IntoResponse + Send,
-    RequestBodyType: Body + Send + 'static,
+    RequestBodyType: Body<Data = Bytes> + Send + 'static,
+    RequestBodyType::Error: Into<BoxError>,
 {
     type Response = Result<Response, CallbackErrorType>;
     type Error = InnerServiceType::Error;
@@ -84,9 +169,20 @@ where
     }
 
     fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        let request_method = req.method().clone();
         let request_headers = req.headers().clone();
-        let mut inner = self.inner.clone();
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
         let callback = self.callback.clone();
+        let request_body_failed = Arc::new(AtomicBool::new(false));
+        let req = req.map(|body| {
+            axum::body::Body::new(RequestBodyErrorTracker {
+                body: axum::body::Body::new(body),
+                failed: request_body_failed.clone(),
+            })
+        });
         Box::pin(async move {
             let next_response = inner.call(req).await;
 
@@ -94,7 +190,19 @@ where
                 Ok(next_response) => {
                     let mut response = next_response.into_response();
 
-                    if let Err(e) = callback.as_ref()(&request_headers, response.headers_mut()) {
+                    if request_body_failed.load(Ordering::SeqCst) {
+                        let status = response.status();
+                        if status.is_client_error() || status.is_server_error() {
+                            return Ok(Ok(response));
+                        }
+
+                        log::debug!("Reading the request body failed, responding with 400");
+                        return Ok(Ok(StatusCode::BAD_REQUEST.into_response()));
+                    }
+
+                    if let Err(e) =
+                        callback.as_ref()(&request_method, &request_headers, response.headers_mut())
+                    {
                         Ok(Err(e))
                     } else {
                         Ok(Ok(response))
@@ -105,3 +213,50 @@ where
         })
     }
 }
+
+/// Tells whether the error is caused by a body exceeding its limit, e.g., of `BodyLimitLayer`.
+fn is_length_limit_error(err: &axum::Error) -> bool {
+    let mut source: Option<&(dyn Error + 'static)> = Some(err);
+    while let Some(err) = source {
+        if err.is::<LengthLimitError>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Request body remembering whether reading it failed, e.g., an I/O error or a disconnect, a body
+/// exceeding its limit does not count as failed.
+struct RequestBodyErrorTracker {
+    body: axum::body::Body,
+    failed: Arc<AtomicBool>,
+}
+
+impl Body for RequestBodyErrorTracker {
+    type Data = Bytes;
+    // the error of the tracked body is passed on unwrapped, as extractors look for
+    // `LengthLimitError` through a limited number of `axum::Error` wrappers only
+    type Error = BoxError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = Pin::new(&mut self.body).poll_frame(cx);
+        if let Poll::Ready(Some(Err(err))) = &frame {
+            if !is_length_limit_error(err) {
+                self.failed.store(true, Ordering::SeqCst);
+            }
+        }
+        frame.map(|frame| frame.map(|frame| frame.map_err(axum::Error::into_inner)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
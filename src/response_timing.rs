@@ -0,0 +1,89 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use http_body::Body;
+use tower::{Layer, Service};
+
+/// Writes the time the inner service took to produce the response, in milliseconds, into the
+/// configured response header (e.g., `x-response-time-ms`).
+#[derive(Clone)]
+pub struct ResponseTimingLayer {
+    header_name: HeaderName,
+}
+
+impl ResponseTimingLayer {
+    pub fn new(header_name: HeaderName) -> Self {
+        Self { header_name }
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for ResponseTimingLayer {
+    type Service = ResponseTimingMiddleware<InnerServiceType>;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        ResponseTimingMiddleware {
+            inner,
+            header_name: self.header_name.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ResponseTimingMiddleware<InnerServiceType> {
+    inner: InnerServiceType,
+    header_name: HeaderName,
+}
+
+impl<InnerServiceType, RequestBodyType, InnerResponseType> Service<Request<RequestBodyType>>
+    for ResponseTimingMiddleware<InnerServiceType>
+where
+    InnerServiceType: Service<Request<RequestBodyType>> + Clone + Send + 'static,
+    InnerServiceType::Future:
+        Future<Output = Result<InnerResponseType, InnerServiceType::Error>> + Send,
+    InnerServiceType::Error: Send,
+    InnerResponseType: IntoResponse + Send,
+    RequestBodyType: Body + Send + 'static,
+{
+    type Response = Response;
+    type Error = InnerServiceType::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, InnerServiceType::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<RequestBodyType>) -> Self::Future {
+        let header_name = self.header_name.clone();
+        // the inner service was driven to readiness by poll_ready, so that instance is moved
+        // into the future and a fresh clone is left behind for the next poll_ready
+        let inner_clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner_clone);
+        Box::pin(async move {
+            let started_at = Instant::now();
+            let next_response = inner.call(req).await;
+
+            match next_response {
+                Ok(next_response) => {
+                    let mut response = next_response.into_response();
+
+                    let elapsed_ms = started_at.elapsed().as_millis();
+                    response
+                        .headers_mut()
+                        .insert(header_name, HeaderValue::from(elapsed_ms as u64));
+
+                    Ok(response)
+                }
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::http::{
+    header::{REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS},
+    HeaderName, HeaderValue,
+};
+use tower::Layer;
+
+use crate::response_http_header_mutator::{
+    HeaderMutatorCallback, ResponseHttpHeaderMutatorLayer, ResponseHttpHeaderMutatorMiddleware,
+};
+
+/// Value of the `X-Frame-Options` header.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FrameOptions {
+    Deny,
+    SameOrigin,
+}
+
+impl FrameOptions {
+    fn header_value(self) -> HeaderValue {
+        match self {
+            FrameOptions::Deny => HeaderValue::from_static("DENY"),
+            FrameOptions::SameOrigin => HeaderValue::from_static("SAMEORIGIN"),
+        }
+    }
+}
+
+/// Sets security related headers on every response, built on `ResponseHttpHeaderMutatorLayer`.
+/// Headers already set by the handler are not overwritten.
+///
+/// By default it sets `X-Content-Type-Options: nosniff`, `X-Frame-Options: DENY` and
+/// `Referrer-Policy: strict-origin-when-cross-origin`. `Strict-Transport-Security` is only set if
+/// it is enabled with `hsts`.
+#[derive(Clone)]
+pub struct SecurityHeadersLayer {
+    content_type_options: bool,
+    frame_options: Option<FrameOptions>,
+    referrer_policy: Option<HeaderValue>,
+    hsts: Option<HeaderValue>,
+}
+
+impl SecurityHeadersLayer {
+    pub fn new() -> Self {
+        Self {
+            content_type_options: true,
+            frame_options: Some(FrameOptions::Deny),
+            referrer_policy: Some(HeaderValue::from_static("strict-origin-when-cross-origin")),
+            hsts: None,
+        }
+    }
+
+    /// Enables or disables `X-Content-Type-Options: nosniff`.
+    pub fn content_type_options(mut self, content_type_options: bool) -> Self {
+        self.content_type_options = content_type_options;
+        self
+    }
+
+    /// Sets the value of `X-Frame-Options`, `None` disables the header.
+    pub fn frame_options(mut self, frame_options: Option<FrameOptions>) -> Self {
+        self.frame_options = frame_options;
+        self
+    }
+
+    /// Sets the value of `Referrer-Policy`, `None` disables the header.
+    pub fn referrer_policy(mut self, referrer_policy: Option<HeaderValue>) -> Self {
+        self.referrer_policy = referrer_policy;
+        self
+    }
+
+    /// Enables `Strict-Transport-Security` with the given max age, browsers ignore it on
+    /// plaintext responses.
+    pub fn hsts(mut self, max_age: Duration, include_subdomains: bool) -> Self {
+        let value = if include_subdomains {
+            format!("max-age={}; includeSubDomains", max_age.as_secs())
+        } else {
+            format!("max-age={}", max_age.as_secs())
+        };
+        self.hsts = HeaderValue::from_str(&value).ok();
+        self
+    }
+
+    fn headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        [
+            self.content_type_options
+                .then(|| (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))),
+            self.frame_options
+                .map(|frame_options| (X_FRAME_OPTIONS, frame_options.header_value())),
+            self.referrer_policy
+                .clone()
+                .map(|referrer_policy| (REFERRER_POLICY, referrer_policy)),
+            self.hsts
+                .clone()
+                .map(|hsts| (STRICT_TRANSPORT_SECURITY, hsts)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+impl Default for SecurityHeadersLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<InnerServiceType> Layer<InnerServiceType> for SecurityHeadersLayer {
+    type Service = ResponseHttpHeaderMutatorMiddleware<
+        InnerServiceType,
+        Infallible,
+        HeaderMutatorCallback<Infallible>,
+    >;
+
+    fn layer(&self, inner: InnerServiceType) -> Self::Service {
+        let headers: Arc<[(HeaderName, HeaderValue)]> = self.headers().into();
+        let callback: HeaderMutatorCallback<Infallible> =
+            Box::new(move |_method, _req_headers, res_headers| {
+                for (header_name, header_value) in headers.iter() {
+                    res_headers
+                        .entry(header_name)
+                        .or_insert_with(|| header_value.clone());
+                }
+                Ok(())
+            });
+
+        ResponseHttpHeaderMutatorLayer::new_with_method(callback).layer(inner)
+    }
+}
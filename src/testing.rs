@@ -0,0 +1,20 @@
+use axum_test::TestServer;
+use serde::Serialize;
+
+/// Logs in by posting `login_request` as JSON to `login_path`, and asserts that the login
+/// succeeded. The returned server saves the cookies, so its further requests are authenticated.
+pub async fn login_test_server<LoginRequestType: Serialize>(
+    mut server: TestServer,
+    login_path: &str,
+    login_request: &LoginRequestType,
+) -> TestServer {
+    server.do_save_cookies();
+
+    server
+        .post(login_path)
+        .json(login_request)
+        .await
+        .assert_status_success();
+
+    server
+}
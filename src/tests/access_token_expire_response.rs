@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{http::StatusCode, routing::post, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessTokenExpireResponse, AuthLayer, FnAuthHandler, LoginInfoExtractor,
+        RefreshTokenExtractor, RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+type Revocations = Arc<Mutex<Vec<String>>>;
+
+fn auth_handler(revocations: Revocations) -> FnAuthHandler<LoginInfo> {
+    let refresh_token_revocations = revocations.clone();
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "access-token" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        move |access_token, _login_info| {
+            revocations.lock().unwrap().push(access_token.to_string());
+            Box::pin(async {})
+        },
+    )
+    .with_refresh_token_fns(
+        |_refresh_token| Box::pin(async { Ok(()) }),
+        move |refresh_token| {
+            refresh_token_revocations
+                .lock()
+                .unwrap()
+                .push(refresh_token.to_string());
+            Box::pin(async {})
+        },
+    )
+}
+
+fn routes(revocations: Revocations) -> Router {
+    Router::new()
+        .route("/api/expire-access-token", post(api_expire_access_token))
+        .route("/api/refresh-login", post(api_refresh_login))
+        .route_layer(AuthLayer::new(auth_handler(revocations)))
+}
+
+async fn api_expire_access_token(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> AccessTokenExpireResponse {
+    AccessTokenExpireResponse::new(Some("/"))
+}
+
+async fn api_refresh_login(
+    RefreshTokenExtractor(_refresh_token): RefreshTokenExtractor,
+) -> &'static str {
+    "refreshed"
+}
+
+#[tokio::test]
+async fn expire_access_token_only() {
+    let revocations = Revocations::default();
+    let app = AxumApp::new(routes(revocations.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/expire-access-token")
+        .add_header(
+            "cookie",
+            "access_token=access-token; refresh_token=refresh-token",
+        )
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("access_token").value(), "");
+    assert!(response.maybe_cookie("refresh_token").is_none());
+    assert_eq!(
+        *revocations.lock().unwrap(),
+        vec!["access-token".to_string()]
+    );
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=refresh-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("refreshed");
+}
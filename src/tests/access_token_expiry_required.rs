@@ -0,0 +1,109 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+use time::OffsetDateTime;
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthHandler, AuthLayer, LoginInfoExtractor, RefreshToken, RenewalDecision,
+    },
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        Ok(LoginInfo)
+    }
+
+    // only the `expiring` access tokens have a lifetime
+    async fn access_token_lifetime(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> Option<Range<OffsetDateTime>> {
+        let now = OffsetDateTime::now_utc();
+        (access_token.as_ref() == "expiring")
+            .then(|| now..now + ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn routes(auth_layer: AuthLayer<LoginInfo, AppState>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(auth_layer)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn accept_access_token_without_lifetime_by_default() {
+    let app = AxumApp::new(routes(AuthLayer::new(AppState)));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=non-expiring")
+        .await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn reject_access_token_without_lifetime_if_expiry_is_required() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(AppState).with_access_token_expiry_required(true),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=non-expiring")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=expiring")
+        .await;
+    response.assert_status_ok();
+}
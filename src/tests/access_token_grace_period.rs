@@ -0,0 +1,143 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+use time::OffsetDateTime;
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthHandler, AuthLayer, LoginInfoExtractor, RefreshToken, RenewalDecision,
+    },
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+const GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    // accepts the expired access tokens as well, the grace period is enforced by the auth layer
+    async fn verify_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        Ok(LoginInfo)
+    }
+
+    async fn access_token_lifetime(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> Option<Range<OffsetDateTime>> {
+        let now = OffsetDateTime::now_utc();
+        let expires_at = match access_token.trim_start_matches("kept-") {
+            "just-expired" => now - Duration::from_secs(5),
+            "long-expired" => now - Duration::from_secs(120),
+            "renewed" => now + ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+            _ => return None,
+        };
+        Some(expires_at - ACCESS_TOKEN_EXPIRATION_TIME_DURATION..expires_at)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        if access_token.starts_with("kept-") {
+            RenewalDecision::Keep
+        } else {
+            RenewalDecision::Renew(
+                AccessToken::new("renewed".into()),
+                ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+            )
+        }
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn routes(auth_layer: AuthLayer<LoginInfo, AppState>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(auth_layer)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn accept_just_expired_access_token_once_renewed() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(AppState).with_access_token_grace_period(GRACE_PERIOD),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=just-expired")
+        .await;
+    response.assert_status_ok();
+    let renewed_access_token = response.cookie("access_token");
+    assert_eq!(renewed_access_token.value(), "renewed");
+
+    let response = server
+        .get("/private")
+        .add_header(
+            "cookie",
+            format!("access_token={}", renewed_access_token.value()),
+        )
+        .await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn reject_expired_access_token_not_renewed() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(AppState).with_access_token_grace_period(GRACE_PERIOD),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=kept-just-expired")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn reject_access_token_expired_before_grace_period() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(AppState).with_access_token_grace_period(GRACE_PERIOD),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=long-expired")
+        .await;
+    response.assert_status_unauthorized();
+}
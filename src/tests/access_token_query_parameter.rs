@@ -0,0 +1,76 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor},
+    tests::support::renewing_stub_auth_handler,
+};
+
+const VALID_ACCESS_TOKEN: &str = "valid-access-token";
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    renewing_stub_auth_handler(|access_token| {
+        if access_token.as_str() == VALID_ACCESS_TOKEN {
+            Ok(LoginInfo)
+        } else {
+            Err(StatusCode::BAD_REQUEST)
+        }
+    })
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/ws", get(get_private))
+        .route_layer(
+            AuthLayer::new(state.clone()).with_access_token_query_parameter("access_token"),
+        )
+        .with_state(state)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn access_token_in_query() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/ws")
+        .add_query_param("access_token", VALID_ACCESS_TOKEN)
+        .await;
+    response.assert_status_ok();
+    response.assert_text("private");
+    assert!(response.maybe_cookie("access_token").is_none());
+}
+
+#[tokio::test]
+async fn invalid_access_token_in_query() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/ws")
+        .add_query_param("access_token", "invalid-access-token")
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn access_token_in_cookie_is_renewed() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/ws")
+        .add_header("cookie", format!("access_token={VALID_ACCESS_TOKEN}"))
+        .await;
+    response.assert_status_ok();
+    assert!(response.maybe_cookie("access_token").is_some());
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use axum::{routing::get, Router};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/", get(get_index))
+}
+
+async fn get_index() -> String {
+    "index".repeat(100)
+}
+
+#[tokio::test]
+async fn build_app_with_options() {
+    let app = AxumApp::builder(routes())
+        .health_route("/health")
+        .compression()
+        .json_not_found()
+        .inflight_gauge()
+        .drain_deadline(Duration::from_secs(5))
+        .build();
+    assert_eq!(app.drain_deadline(), Some(Duration::from_secs(5)));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").add_header("accept-encoding", "gzip").await;
+    response.assert_status_ok();
+    assert_eq!(response.header("content-encoding"), "gzip");
+
+    let response = server.get("/health").await;
+    response.assert_status_ok();
+    response.assert_text("ok");
+
+    let response = server.get("/missing").await;
+    response.assert_status_not_found();
+    response.assert_text(r#"{"error":"not_found"}"#);
+
+    assert_eq!(app.inflight(), 0);
+}
+
+#[tokio::test]
+async fn build_app_without_options() {
+    let app = AxumApp::builder(routes()).build();
+    assert_eq!(app.drain_deadline(), None);
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").add_header("accept-encoding", "gzip").await;
+    response.assert_text("index".repeat(100));
+    assert!(response.headers().get("content-encoding").is_none());
+
+    let response = server.get("/health").await;
+    response.assert_status_not_found();
+}
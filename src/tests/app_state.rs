@@ -1,4 +1,9 @@
-use axum::{routing::get, Router};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::{extract::State, routing::get, Router};
 
 use crate::app::AxumApp;
 
@@ -21,3 +26,60 @@ async fn get_index_page() {
     let response = server.get("/").await;
     response.assert_text("index");
 }
+
+async fn get_nested_index() -> &'static str {
+    "nested-index"
+}
+
+#[tokio::test]
+async fn map_router() {
+    let mut app = AxumApp::new(routes(AppState));
+    app.map_router(|router| router.route("/nested", get(get_nested_index)));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+
+    let response = server.get("/nested").await;
+    response.assert_text("nested-index");
+}
+
+#[tokio::test]
+async fn router_mut() {
+    let mut app = AxumApp::new(routes(AppState));
+    *app.router_mut() = Router::new().nest("/app", app.router().clone());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/app").await;
+    response.assert_text("index");
+}
+
+#[derive(Clone)]
+struct CounterState {
+    counter: Arc<AtomicUsize>,
+}
+
+fn counter_routes() -> Router {
+    Router::new()
+        .route("/count", get(get_count))
+        .with_state(CounterState {
+            counter: Arc::new(AtomicUsize::new(0)),
+        })
+}
+
+async fn get_count(State(state): State<CounterState>) -> String {
+    (state.counter.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+}
+
+#[tokio::test]
+async fn router_factory_isolates_state() {
+    let app = AxumApp::new_with(counter_routes).with_health_route("/health");
+    let server_0 = app.spawn_test_server().unwrap();
+    let server_1 = app.spawn_test_server().unwrap();
+
+    server_0.get("/count").await.assert_text("1");
+    server_0.get("/count").await.assert_text("2");
+    server_1.get("/count").await.assert_text("1");
+
+    server_1.get("/health").await.assert_text("ok");
+}
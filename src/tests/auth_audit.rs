@@ -0,0 +1,219 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::post, Router};
+use time::OffsetDateTime;
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthAudit, AuthHandler, AuthLayer, AuthLogoutResponse,
+        AuthSessionResponse, RefreshToken, RefreshTokenResponse, RefreshTokenRotation,
+        RefreshTokenRotationResponse, RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+}
+
+#[derive(Clone, Default)]
+struct AuditLog(Arc<Mutex<Vec<String>>>);
+
+impl AuditLog {
+    fn push(
+        &self,
+        event: &str,
+        access_token_response: &AccessTokenResponse,
+        login_info: Option<&LoginInfo>,
+    ) {
+        let mut entry = format!(
+            "{event} {}",
+            access_token_response
+                .token()
+                .trim_start_matches("token-of-")
+        );
+        if let Some(login_info) = login_info {
+            entry.push_str(&format!(" as {}", login_info.loginname));
+        }
+        self.0.lock().unwrap().push(entry);
+    }
+}
+
+impl AuthAudit<LoginInfo> for AuditLog {
+    fn on_login(
+        &self,
+        access_token_response: &AccessTokenResponse,
+        login_info: Option<&LoginInfo>,
+    ) {
+        self.push("login", access_token_response, login_info);
+    }
+
+    fn on_refresh(
+        &self,
+        access_token_response: &AccessTokenResponse,
+        login_info: Option<&LoginInfo>,
+    ) {
+        self.push("refresh", access_token_response, login_info);
+    }
+
+    fn on_logout(&self, login_info: &LoginInfo) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(format!("logout {}", login_info.loginname));
+    }
+}
+
+/// Accepts the `token-of-<loginname>` access tokens and every refresh token, a rotated refresh
+/// token is replaced with a new session of alice.
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        access_token
+            .strip_prefix("token-of-")
+            .map(|loginname| LoginInfo {
+                loginname: loginname.to_string(),
+            })
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+
+    async fn rotate_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> RefreshTokenRotation {
+        RefreshTokenRotation::Rotated(AuthSessionResponse::new(
+            access_token_response(),
+            RefreshTokenResponse::with_offset_date_time(
+                "rotated-refresh-token",
+                OffsetDateTime::now_utc() + time::Duration::hours(1),
+                "/",
+            ),
+        ))
+    }
+}
+
+fn routes(audit_log: AuditLog) -> Router {
+    Router::new()
+        .route("/api/login", post(api_login))
+        .route("/api/refresh", post(api_refresh))
+        .route("/api/logout", post(api_logout))
+        .route_layer(AuthLayer::new(AppState).with_audit(audit_log))
+}
+
+fn access_token_response() -> AccessTokenResponse {
+    AccessTokenResponse::with_offset_date_time(
+        "token-of-alice",
+        OffsetDateTime::now_utc() + time::Duration::minutes(5),
+        None,
+    )
+}
+
+async fn api_login() -> AccessTokenResponse {
+    access_token_response()
+}
+
+async fn api_refresh() -> RefreshTokenRotationResponse {
+    RefreshTokenRotationResponse::new(None::<String>, None::<String>)
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+}
+
+#[tokio::test]
+async fn audit_login_and_logout() {
+    let audit_log = AuditLog::default();
+    let app = AxumApp::new(routes(audit_log.clone()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login").await.assert_status_ok();
+    server.post("/api/logout").await.assert_status_ok();
+
+    assert_eq!(
+        *audit_log.0.lock().unwrap(),
+        vec!["login alice".to_string(), "logout alice".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn audit_relogin_with_valid_refresh_token_as_login() {
+    let audit_log = AuditLog::default();
+    let app = AxumApp::new(routes(audit_log.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    server
+        .post("/api/login")
+        .add_header(
+            "cookie",
+            "access_token=token-of-bob; refresh_token=refresh-token",
+        )
+        .await
+        .assert_status_ok();
+
+    assert_eq!(
+        *audit_log.0.lock().unwrap(),
+        vec!["login alice as bob".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn audit_refresh_token_rotation_as_refresh() {
+    let audit_log = AuditLog::default();
+    let app = AxumApp::new(routes(audit_log.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    server
+        .post("/api/refresh")
+        .add_header("cookie", "refresh_token=refresh-token")
+        .await
+        .assert_status_ok();
+
+    assert_eq!(
+        *audit_log.0.lock().unwrap(),
+        vec!["refresh alice".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn audit_skips_unauthenticated_logout() {
+    let audit_log = AuditLog::default();
+    let app = AxumApp::new(routes(audit_log.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    server.post("/api/logout").await.assert_status_ok();
+
+    assert!(audit_log.0.lock().unwrap().is_empty());
+}
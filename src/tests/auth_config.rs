@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthConfig, AuthLayer, FnAuthHandler, LoginInfoExtractor,
+        RenewalDecision, SameSite,
+    },
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes(config: AuthConfig) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/login", post(api_login))
+        .route_layer(AuthLayer::with_config(auth_handler(), config))
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn api_login() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta(
+        AccessToken::new("valid".into()),
+        ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+        None,
+    )
+}
+
+#[test]
+fn new_uses_default_config() {
+    assert_eq!(
+        AuthLayer::new(auth_handler()).config(),
+        &AuthConfig::default()
+    );
+}
+
+#[test]
+fn builders_update_config() {
+    let layer = AuthLayer::with_config(auth_handler(), AuthConfig::default())
+        .with_cookie_names("session", "session_refresh")
+        .with_base_path("/api/")
+        .with_vary_cookie(false)
+        .with_access_token_same_site(SameSite::Lax)
+        .with_secure_cookies(false)
+        .with_access_token_path("/app")
+        .with_cookie_domain("example.com");
+
+    assert_eq!(
+        layer.config(),
+        &AuthConfig {
+            access_token_cookie_name: "session".into(),
+            refresh_token_cookie_name: "session_refresh".into(),
+            base_path: "/api".into(),
+            vary_cookie: false,
+            access_token_same_site: SameSite::Lax,
+            secure: false,
+            access_token_path: "/app".into(),
+            domain: Some("example.com".into()),
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn middleware_reads_config() {
+    let app = AxumApp::new(routes(AuthConfig {
+        access_token_cookie_name: "session".into(),
+        base_path: "/app/".into(),
+        vary_cookie: false,
+        ..Default::default()
+    }));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/login").await;
+    let cookie = response.cookie("session");
+    assert_eq!(cookie.value(), "valid");
+    assert_eq!(cookie.path(), Some("/app"));
+    assert!(response.maybe_header("vary").is_none());
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "session=valid")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialize_partial_config() {
+    let config: AuthConfig = serde_json::from_str(
+        r#"{"access_token_cookie_name": "session", "access_token_same_site": "None", "login_redirect": "/login"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config,
+        AuthConfig {
+            access_token_cookie_name: "session".into(),
+            access_token_same_site: SameSite::None,
+            login_redirect: Some("/login".into()),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn validate_config() {
+    assert_eq!(AuthConfig::default().validate(), Ok(()));
+
+    for config in [
+        AuthConfig {
+            access_token_cookie_name: String::new(),
+            ..Default::default()
+        },
+        AuthConfig {
+            refresh_token_cookie_name: "refresh token".into(),
+            ..Default::default()
+        },
+        AuthConfig {
+            refresh_token_cookie_name: "access_token".into(),
+            ..Default::default()
+        },
+        AuthConfig {
+            host_cookie_prefix: true,
+            access_token_path: "/app".into(),
+            ..Default::default()
+        },
+        AuthConfig {
+            access_token_cookie_name: "__Secure-access_token".into(),
+            secure: false,
+            ..Default::default()
+        },
+        AuthConfig {
+            access_token_same_site: SameSite::None,
+            secure: false,
+            ..Default::default()
+        },
+    ] {
+        assert!(config.validate().is_err(), "{config:?}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "Invalid AuthLayer configuration")]
+fn layering_invalid_config_panics() {
+    let _: Router = Router::new()
+        .route("/private", get(get_private))
+        .route_layer(AuthLayer::with_config(
+            auth_handler(),
+            AuthConfig {
+                access_token_cookie_name: "access;token".into(),
+                ..Default::default()
+            },
+        ));
+}
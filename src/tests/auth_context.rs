@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthContext, AuthContextExtractor, AuthHandler, AuthLayer, RefreshToken,
+        RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        if access_token.as_str() == "valid-access-token" {
+            Ok(LoginInfo {
+                tenant_id: "tenant".into(),
+            })
+        } else {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+
+    async fn extend_auth_context(
+        &mut self,
+        _access_token: &AccessToken,
+        login_info: &Arc<LoginInfo>,
+        auth_context: &mut AuthContext,
+    ) {
+        auth_context.insert(TenantId(login_info.tenant_id.clone()));
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo {
+    tenant_id: String,
+}
+
+#[derive(Clone)]
+struct TenantId(String);
+
+#[derive(Clone)]
+struct Permissions;
+
+fn routes(state: AppState) -> Router {
+    Router::new()
+        .route("/tenant", get(get_tenant))
+        .route("/permissions", get(get_permissions))
+        .route_layer(AuthLayer::new(state.clone()))
+        .with_state(state)
+}
+
+async fn get_tenant(
+    AuthContextExtractor(TenantId(tenant_id)): AuthContextExtractor<TenantId>,
+) -> String {
+    tenant_id
+}
+
+async fn get_permissions(
+    AuthContextExtractor(_permissions): AuthContextExtractor<Permissions>,
+) -> &'static str {
+    "permissions"
+}
+
+#[tokio::test]
+async fn extract_value_inserted_by_auth_handler() {
+    let app = AxumApp::new(routes(AppState));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=valid-access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("tenant");
+}
+
+#[tokio::test]
+async fn unauthenticated_request_is_rejected() {
+    let app = AxumApp::new(routes(AppState));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/tenant").await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=invalid-access-token")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn value_not_inserted_by_auth_handler() {
+    let app = AxumApp::new(routes(AppState));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/permissions")
+        .add_header("cookie", "access_token=valid-access-token")
+        .await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+}
@@ -0,0 +1,184 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, Cookie, LoginInfoExtractor,
+        RefreshToken, RenewalDecision,
+    },
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+/// Accepts the access tokens starting with the given prefix and renews them on every request.
+#[derive(Clone)]
+struct Realm {
+    access_token_prefix: &'static str,
+}
+
+#[derive(Clone)]
+struct AdminLogin;
+
+#[derive(Clone)]
+struct UserLogin;
+
+macro_rules! impl_auth_handler {
+    ($login_info_type:ident) => {
+        #[async_trait]
+        impl AuthHandler<$login_info_type> for Realm {
+            async fn verify_access_token(
+                &mut self,
+                access_token: &AccessToken,
+            ) -> Result<$login_info_type, StatusCode> {
+                access_token
+                    .starts_with(self.access_token_prefix)
+                    .then_some($login_info_type)
+                    .ok_or(StatusCode::UNAUTHORIZED)
+            }
+
+            async fn update_access_token(
+                &mut self,
+                access_token: &AccessToken,
+                _login_info: &Arc<$login_info_type>,
+            ) -> RenewalDecision {
+                RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+            }
+
+            async fn revoke_access_token(
+                &mut self,
+                _access_token: &AccessToken,
+                _login_info: &Arc<$login_info_type>,
+            ) {
+            }
+
+            async fn verify_refresh_token(
+                &mut self,
+                _refresh_token: &RefreshToken,
+            ) -> Result<(), StatusCode> {
+                Err(StatusCode::UNAUTHORIZED)
+            }
+
+            async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+        }
+    };
+}
+
+impl_auth_handler!(AdminLogin);
+impl_auth_handler!(UserLogin);
+
+fn routes() -> Router {
+    Router::new()
+        .route("/admin", get(get_admin))
+        .route("/user", get(get_user))
+        .route("/api/admin-login", post(api_admin_login))
+        .route("/api/user-login", post(api_user_login))
+        .route_layer(
+            AuthLayer::<UserLogin, _>::new(Realm {
+                access_token_prefix: "user-",
+            })
+            .with_cookie_names("user_access_token", "user_refresh_token"),
+        )
+        .route_layer(
+            AuthLayer::<AdminLogin, _>::new(Realm {
+                access_token_prefix: "admin-",
+            })
+            .with_cookie_names("admin_access_token", "admin_refresh_token"),
+        )
+}
+
+async fn get_admin(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<AdminLogin>,
+) -> &'static str {
+    "admin"
+}
+
+async fn get_user(LoginInfoExtractor(_login_info): LoginInfoExtractor<UserLogin>) -> &'static str {
+    "user"
+}
+
+async fn api_admin_login() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta("admin-token", ACCESS_TOKEN_EXPIRATION_TIME_DURATION, None)
+        .with_cookie_name("admin_access_token")
+}
+
+async fn api_user_login() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta("user-token", ACCESS_TOKEN_EXPIRATION_TIME_DURATION, None)
+}
+
+fn set_cookie_names(response: &axum_test::TestResponse) -> Vec<String> {
+    let mut names = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|header_value| {
+            Cookie::parse(header_value.to_str().unwrap().to_string())
+                .unwrap()
+                .name()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn login_sets_cookie_of_targeted_realm() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/admin-login").await;
+    assert_eq!(set_cookie_names(&response), vec!["admin_access_token"]);
+    assert_eq!(response.cookie("admin_access_token").value(), "admin-token");
+
+    let response = server.post("/api/user-login").await;
+    assert_eq!(set_cookie_names(&response), vec!["user_access_token"]);
+    assert_eq!(response.cookie("user_access_token").value(), "user-token");
+}
+
+#[tokio::test]
+async fn realms_verify_and_renew_their_own_cookies() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/admin")
+        .add_header("cookie", "admin_access_token=admin-token")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(set_cookie_names(&response), vec!["admin_access_token"]);
+
+    let response = server
+        .get("/user")
+        .add_header("cookie", "admin_access_token=admin-token")
+        .await;
+    response.assert_status_unauthorized();
+
+    // a token of the other realm is not accepted in the cookie of this realm
+    let response = server
+        .get("/user")
+        .add_header("cookie", "user_access_token=admin-token")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .get("/user")
+        .add_header(
+            "cookie",
+            "admin_access_token=admin-token; user_access_token=user-token",
+        )
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        set_cookie_names(&response),
+        vec!["admin_access_token", "user_access_token"]
+    );
+    assert_eq!(response.cookie("admin_access_token").value(), "admin-token");
+    assert_eq!(response.cookie("user_access_token").value(), "user-token");
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, BoxError, Router};
+
+use crate::{
+    app::{AxumApp, RunServerError},
+    auth::{AccessToken, AuthHandler, AuthLayer, RefreshToken, RenewalDecision},
+};
+
+#[derive(Clone)]
+struct AppState {
+    token_store_reachable: bool,
+}
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn ready(&self) -> Result<(), BoxError> {
+        if self.token_store_reachable {
+            Ok(())
+        } else {
+            Err("token store is unreachable".into())
+        }
+    }
+
+    async fn verify_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        Ok(LoginInfo)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn app(state: AppState) -> AxumApp {
+    AxumApp::new(
+        Router::new()
+            .route("/", get(get_index))
+            .route_layer(AuthLayer::new(state.clone())),
+    )
+    .with_auth_validation(state)
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[tokio::test]
+async fn auth_not_ready() {
+    let mut app = app(AppState {
+        token_store_reachable: false,
+    });
+
+    let result = app.spawn_server("127.0.0.1:0".parse().unwrap()).await;
+    assert!(
+        matches!(result, Err(RunServerError::AuthNotReady(e)) if e.to_string() == "token store is unreachable")
+    );
+    app.join().await;
+}
+
+#[tokio::test]
+async fn auth_ready() {
+    let mut app = app(AppState {
+        token_store_reachable: true,
+    });
+
+    let (local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    assert!(tokio::net::TcpStream::connect(local_address).await.is_ok());
+
+    app.stop_server();
+    app.join().await;
+}
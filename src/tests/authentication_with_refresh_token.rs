@@ -1,9 +1,14 @@
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use axum::{
     extract::State,
     http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
     Json, Router,
 };
@@ -12,8 +17,11 @@ use crate::{
     app::AxumApp,
     auth::{
         AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
-        LoginInfoExtractor, RefreshToken, RefreshTokenExtractor, RefreshTokenResponse,
+        AuthSessionResponse, LoginInfoExtractor, RefreshToken, RefreshTokenExtractor,
+        RefreshTokenExtractorWithRejection, RefreshTokenRejection, RefreshTokenResponse,
+        RefreshTokenRotation, RefreshTokenRotationResponse, RenewalDecision,
     },
+    testing::login_test_server,
 };
 use parking_lot::Mutex;
 use uuid::Uuid;
@@ -25,6 +33,8 @@ const REFRESH_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(24
 struct AppState {
     logins_by_access_token: Arc<Mutex<BTreeMap<AccessToken, LoginInfo>>>,
     access_tokens_by_refresh_token: Arc<Mutex<BTreeMap<RefreshToken, AccessToken>>>,
+    rotated_refresh_tokens: Arc<Mutex<BTreeMap<RefreshToken, RefreshToken>>>,
+    expired_refresh_tokens: Arc<Mutex<BTreeSet<RefreshToken>>>,
 }
 
 impl AppState {
@@ -32,6 +42,8 @@ impl AppState {
         Self {
             logins_by_access_token: Arc::new(Mutex::new(BTreeMap::new())),
             access_tokens_by_refresh_token: Arc::new(Mutex::new(BTreeMap::new())),
+            rotated_refresh_tokens: Arc::new(Mutex::new(BTreeMap::new())),
+            expired_refresh_tokens: Arc::new(Mutex::new(BTreeSet::new())),
         }
     }
 
@@ -96,6 +108,61 @@ impl AppState {
         Some(access_token_response)
     }
 
+    fn rotate(&mut self, refresh_token: &RefreshToken) -> RefreshTokenRotation {
+        let mut rotated_refresh_token = None;
+        while let Some(next_refresh_token) = self
+            .rotated_refresh_tokens
+            .lock()
+            .get(rotated_refresh_token.as_ref().unwrap_or(refresh_token))
+            .cloned()
+        {
+            rotated_refresh_token = Some(next_refresh_token);
+        }
+
+        if let Some(latest_refresh_token) = rotated_refresh_token {
+            self.logout(&latest_refresh_token);
+            return RefreshTokenRotation::ReuseDetected;
+        }
+
+        let Some(access_token) = self
+            .access_tokens_by_refresh_token
+            .lock()
+            .remove(refresh_token)
+        else {
+            return RefreshTokenRotation::Rejected(StatusCode::BAD_REQUEST);
+        };
+
+        let Some(login_info) = self.logins_by_access_token.lock().remove(&access_token) else {
+            return RefreshTokenRotation::Rejected(StatusCode::BAD_REQUEST);
+        };
+
+        let new_access_token = AccessToken::new(Uuid::new_v4().as_hyphenated().to_string());
+        let new_refresh_token = RefreshToken::new(Uuid::new_v4().as_hyphenated().to_string());
+
+        self.logins_by_access_token
+            .lock()
+            .insert(new_access_token.clone(), login_info);
+        self.access_tokens_by_refresh_token
+            .lock()
+            .insert(new_refresh_token.clone(), new_access_token.clone());
+        self.rotated_refresh_tokens
+            .lock()
+            .insert(refresh_token.clone(), new_refresh_token.clone());
+
+        RefreshTokenRotation::Rotated(AuthSessionResponse::new(
+            AccessTokenResponse::with_time_delta(
+                new_access_token,
+                ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+                None,
+            ),
+            RefreshTokenResponse::with_time_delta(
+                new_refresh_token,
+                REFRESH_TOKEN_EXPIRATION_TIME_DURATION,
+                "/api/refresh-login",
+            ),
+        ))
+    }
+
     fn logout(&mut self, refresh_token: &RefreshToken) {
         if let Some(access_token) = self
             .access_tokens_by_refresh_token
@@ -131,8 +198,8 @@ impl AuthHandler<LoginInfo> for AppState {
         &mut self,
         access_token: &AccessToken,
         _login_info: &Arc<LoginInfo>,
-    ) -> Option<(AccessToken, Duration)> {
-        Some((access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION))
+    ) -> RenewalDecision {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
     }
 
     async fn revoke_access_token(
@@ -152,6 +219,10 @@ impl AuthHandler<LoginInfo> for AppState {
         &mut self,
         refresh_token: &RefreshToken,
     ) -> Result<(), StatusCode> {
+        if self.expired_refresh_tokens.lock().contains(refresh_token) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
         self.access_tokens_by_refresh_token
             .lock()
             .contains_key(refresh_token)
@@ -162,6 +233,10 @@ impl AuthHandler<LoginInfo> for AppState {
     async fn revoke_refresh_token(&mut self, refresh_token: &RefreshToken) {
         self.logout(refresh_token);
     }
+
+    async fn rotate_refresh_token(&mut self, refresh_token: &RefreshToken) -> RefreshTokenRotation {
+        self.rotate(refresh_token)
+    }
 }
 
 fn routes(state: AppState) -> Router {
@@ -172,6 +247,11 @@ fn routes(state: AppState) -> Router {
         .route("/api/login", post(api_login))
         .route("/api/logout", post(api_logout))
         .route("/api/refresh-login", post(api_refresh_login))
+        .route("/api/refresh-login/rotate", post(api_rotate_refresh_token))
+        .route(
+            "/api/refresh-login-or-redirect",
+            post(api_refresh_login_or_redirect),
+        )
         .route_layer(AuthLayer::new(state.clone()))
         .with_state(state)
 }
@@ -213,25 +293,15 @@ struct LoginResponse {
 async fn api_login(
     State(mut state): State<AppState>,
     Json(login_request): Json<LoginRequest>,
-) -> Result<
-    (
-        StatusCode,
-        AccessTokenResponse,
-        RefreshTokenResponse,
-        Json<LoginResponse>,
-    ),
-    StatusCode,
-> {
+) -> Result<(AuthSessionResponse, Json<LoginResponse>), StatusCode> {
     let (access_token, refresh_token, _login_info) = state
         .login(&login_request.loginname, login_request.password)
-        .ok_or_else(|| StatusCode::BAD_REQUEST)?;
+        .ok_or(StatusCode::BAD_REQUEST)?;
 
     log::info!("User logged in, loginname = '{}'", login_request.loginname);
 
     Ok((
-        StatusCode::OK,
-        access_token,
-        refresh_token,
+        AuthSessionResponse::new(access_token, refresh_token),
         Json(LoginResponse {
             loginname: login_request.loginname,
         }),
@@ -244,7 +314,36 @@ async fn api_refresh_login(
 ) -> Result<(StatusCode, AccessTokenResponse), StatusCode> {
     let access_token = state
         .refresh(refresh_token)
-        .ok_or_else(|| StatusCode::BAD_REQUEST)?;
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    Ok((StatusCode::OK, access_token))
+}
+
+async fn api_rotate_refresh_token() -> RefreshTokenRotationResponse {
+    RefreshTokenRotationResponse::new(Some("/"), Some("/api/refresh-login"))
+}
+
+struct RedirectToLogin;
+
+impl IntoResponse for RedirectToLogin {
+    fn into_response(self) -> Response {
+        Redirect::to("/login").into_response()
+    }
+}
+
+impl RefreshTokenRejection for RedirectToLogin {
+    fn from_status_code(_status_code: StatusCode) -> Self {
+        RedirectToLogin
+    }
+}
+
+async fn api_refresh_login_or_redirect(
+    refresh_token: RefreshTokenExtractorWithRejection<RedirectToLogin>,
+    State(mut state): State<AppState>,
+) -> Result<(StatusCode, AccessTokenResponse), StatusCode> {
+    let access_token = state
+        .refresh(refresh_token.into_refresh_token())
+        .ok_or(StatusCode::BAD_REQUEST)?;
 
     Ok((StatusCode::OK, access_token))
 }
@@ -279,16 +378,15 @@ async fn get_private_page_unauthenticated() {
 #[tokio::test]
 async fn get_private_page_authenticated() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/private").await;
     response.assert_text("private");
@@ -307,16 +405,15 @@ async fn get_hybrid_page_unauthenticated() {
 #[tokio::test]
 async fn get_hybrid_page_authenticated() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/hybrid").await;
     response.assert_status_ok();
@@ -326,16 +423,15 @@ async fn get_hybrid_page_authenticated() {
 #[tokio::test]
 async fn expired_access_token() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/private").await;
     response.assert_status_ok();
@@ -386,3 +482,120 @@ async fn login_then_logout() {
     response.assert_status_ok();
     response.assert_text("unauthenticated");
 }
+
+#[tokio::test]
+async fn refresh_login_or_redirect() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server.post("/api/refresh-login-or-redirect").await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(response.header("location"), "/login");
+
+    server
+        .post("/api/login")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+
+    let response = server.post("/api/refresh-login-or-redirect").await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn refresh_token_verification_status_propagated() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/login")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+    let refresh_token = response.cookie("refresh_token").value().to_string();
+
+    state
+        .expired_refresh_tokens
+        .lock()
+        .insert(RefreshToken::new(refresh_token.clone()));
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", format!("refresh_token={refresh_token}"))
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=unknown-refresh-token")
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn login_sets_access_and_refresh_token_cookies() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/login")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+    response.assert_status_ok();
+
+    assert!(response.maybe_cookie("access_token").is_some());
+    assert_eq!(
+        response.cookie("refresh_token").path(),
+        Some("/api/refresh-login")
+    );
+}
+
+#[tokio::test]
+async fn rotate_refresh_token_with_reuse_detection() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/login")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+    let refresh_token = response.cookie("refresh_token");
+
+    let response = server
+        .post("/api/refresh-login/rotate")
+        .add_header("cookie", format!("refresh_token={}", refresh_token.value()))
+        .await;
+    response.assert_status_ok();
+    let rotated_refresh_token = response.cookie("refresh_token");
+    assert_ne!(rotated_refresh_token.value(), refresh_token.value());
+    assert!(response.maybe_cookie("access_token").is_some());
+
+    let response = server
+        .post("/api/refresh-login/rotate")
+        .add_header("cookie", format!("refresh_token={}", refresh_token.value()))
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(response.cookie("refresh_token").value(), "");
+
+    // the whole token family is revoked after the reuse
+    let response = server
+        .post("/api/refresh-login/rotate")
+        .add_header(
+            "cookie",
+            format!("refresh_token={}", rotated_refresh_token.value()),
+        )
+        .await;
+    response.assert_status_bad_request();
+}
@@ -11,9 +11,10 @@ use axum::{
 use crate::{
     app::AxumApp,
     auth::{
-        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
-        LoginInfoExtractor, RefreshToken,
+        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLoginRedirectResponse,
+        AuthLogoutResponse, LoginInfoExtractor, LoginStatus, RefreshToken, RenewalDecision,
     },
+    testing::login_test_server,
 };
 use parking_lot::Mutex;
 use uuid::Uuid;
@@ -78,8 +79,8 @@ impl AuthHandler<LoginInfo> for AppState {
         &mut self,
         access_token: &AccessToken,
         _login_info: &Arc<LoginInfo>,
-    ) -> Option<(AccessToken, Duration)> {
-        Some((access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION))
+    ) -> RenewalDecision {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
     }
 
     async fn revoke_access_token(
@@ -107,8 +108,12 @@ fn routes(state: AppState) -> Router {
         .route("/public", get(get_public))
         .route("/private", get(get_private))
         .route("/hybrid", get(get_hybrid))
+        .route("/login-status", get(get_login_status))
+        .route("/loginname", get(get_loginname))
         .route("/api/login", post(api_login))
+        .route("/api/login-redirect", post(api_login_redirect))
         .route("/api/logout", post(api_logout))
+        .route("/api/logout-json", post(api_logout_json))
         .route_layer(AuthLayer::new(state.clone()))
         .with_state(state)
 }
@@ -131,6 +136,22 @@ async fn get_hybrid(login_info: Option<LoginInfoExtractor<LoginInfo>>) -> &'stat
     }
 }
 
+async fn get_loginname(
+    crate::auth::LoginInfo(login_info): crate::auth::LoginInfo<LoginInfo>,
+) -> String {
+    tokio::spawn(async move { login_info.loginname })
+        .await
+        .unwrap()
+}
+
+async fn get_login_status(login_status: LoginStatus<LoginInfo>) -> String {
+    match login_status {
+        LoginStatus::Anonymous => "anonymous".into(),
+        LoginStatus::Invalid(status_code) => format!("invalid {}", status_code.as_u16()),
+        LoginStatus::Authenticated(login_info) => format!("authenticated {}", login_info.loginname),
+    }
+}
+
 #[derive(Clone)]
 struct LoginInfo {
     loginname: String,
@@ -153,7 +174,7 @@ async fn api_login(
 ) -> Result<(StatusCode, AccessTokenResponse, Json<LoginResponse>), StatusCode> {
     let (access_token, _login_info) = state
         .login(&login_request.loginname, login_request.password)
-        .ok_or_else(|| StatusCode::BAD_REQUEST)?;
+        .ok_or(StatusCode::BAD_REQUEST)?;
 
     log::info!("User logged in, loginname = '{}'", login_request.loginname);
 
@@ -166,12 +187,40 @@ async fn api_login(
     ))
 }
 
+async fn api_login_redirect(
+    State(mut state): State<AppState>,
+    Json(login_request): Json<LoginRequest>,
+) -> Result<AuthLoginRedirectResponse, StatusCode> {
+    let (access_token, _login_info) = state
+        .login(&login_request.loginname, login_request.password)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    Ok(AuthLoginRedirectResponse::with_access_token_response(
+        access_token,
+        "/dashboard",
+    ))
+}
+
 async fn api_logout(
     LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
 ) -> Result<AuthLogoutResponse, StatusCode> {
     Ok(AuthLogoutResponse::new(Some("/"), Some("/")))
 }
 
+async fn api_logout_json(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> (AuthLogoutResponse, Json<LogoutResponse>) {
+    (
+        AuthLogoutResponse::new(Some("/"), Some("/")),
+        Json(LogoutResponse { logged_out: true }),
+    )
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LogoutResponse {
+    logged_out: bool,
+}
+
 #[tokio::test]
 async fn get_public_page() {
     let app = AxumApp::new(routes(AppState::new()));
@@ -194,16 +243,15 @@ async fn get_private_page_unauthenticated() {
 #[tokio::test]
 async fn get_private_page_authenticated() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/private").await;
     response.assert_text("private");
@@ -222,16 +270,15 @@ async fn get_hybrid_page_unauthenticated() {
 #[tokio::test]
 async fn get_hybrid_page_authenticated() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/hybrid").await;
     response.assert_status_ok();
@@ -241,16 +288,15 @@ async fn get_hybrid_page_authenticated() {
 #[tokio::test]
 async fn expired_access_token() {
     let app = AxumApp::new(routes(AppState::new()));
-    let mut server = app.spawn_test_server().unwrap();
-    server.do_save_cookies();
-
-    server
-        .post("/api/login")
-        .json(&LoginRequest {
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
             loginname: "loginname".into(),
             password: "password".into(),
-        })
-        .await;
+        },
+    )
+    .await;
 
     let response = server.get("/private").await;
     response.assert_status_ok();
@@ -290,3 +336,109 @@ async fn login_then_logout() {
     response.assert_status_ok();
     response.assert_text("unauthenticated");
 }
+
+#[tokio::test]
+async fn get_login_status_anonymous() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/login-status").await;
+    response.assert_status_ok();
+    response.assert_text("anonymous");
+}
+
+#[tokio::test]
+async fn get_login_status_invalid() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/login-status")
+        .add_header("cookie", "access_token=invalid-access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("invalid 400");
+}
+
+#[tokio::test]
+async fn get_login_status_authenticated() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/login-status").await;
+    response.assert_status_ok();
+    response.assert_text("authenticated loginname");
+}
+
+#[tokio::test]
+async fn get_owned_login_info() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server.get("/loginname").await;
+    response.assert_status_unauthorized();
+
+    server
+        .post("/api/login")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+
+    let response = server.get("/loginname").await;
+    response.assert_status_ok();
+    response.assert_text("loginname");
+}
+
+#[tokio::test]
+async fn login_with_redirect() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server
+        .post("/api/login-redirect")
+        .json(&LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        })
+        .await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(response.header("location"), "/dashboard");
+    assert!(response.maybe_cookie("access_token").is_some());
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn logout_with_json_body() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "loginname".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.post("/api/logout-json").await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("access_token").value(), "");
+    assert!(response.json::<LogoutResponse>().logged_out);
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+}
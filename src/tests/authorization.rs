@@ -12,9 +12,11 @@ use axum::{
 use crate::{
     app::AxumApp,
     auth::{
-        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
-        LoginInfoExtractor, RefreshToken,
+        AccessToken, AccessTokenResponse, AuthError, AuthHandler, AuthLayer, AuthLogoutResponse,
+        Authorized, LoginInfoExtractor, RefreshToken, RenewalDecision, RequireRoleIn, RoleGuard,
+        Roles,
     },
+    testing::login_test_server,
 };
 use parking_lot::Mutex;
 use uuid::Uuid;
@@ -42,6 +44,8 @@ impl AppState {
 
         let role = match loginname.as_str() {
             "admin" => "admin",
+            "moderator" => "moderator",
+            "banned" => "banned",
             _ => "regular",
         }
         .into();
@@ -74,19 +78,26 @@ impl AuthHandler<LoginInfo> for AppState {
         &mut self,
         access_token: &AccessToken,
     ) -> Result<LoginInfo, StatusCode> {
-        self.logins
+        let login_info = self
+            .logins
             .lock()
             .get(access_token)
             .cloned()
-            .ok_or_else(|| StatusCode::BAD_REQUEST)
+            .ok_or(AuthError::InvalidAccessToken)?;
+
+        if login_info.role == "banned" {
+            Err(AuthError::Forbidden.into())
+        } else {
+            Ok(login_info)
+        }
     }
 
     async fn update_access_token(
         &mut self,
         access_token: &AccessToken,
         _login_info: &Arc<LoginInfo>,
-    ) -> Option<(AccessToken, Duration)> {
-        Some((access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION))
+    ) -> RenewalDecision {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
     }
 
     async fn revoke_access_token(
@@ -112,6 +123,9 @@ impl AuthHandler<LoginInfo> for AppState {
 fn routes(state: AppState) -> Router {
     Router::new()
         .route("/admin-page", get(get_admin_page))
+        .route("/guarded-admin-page", get(get_guarded_admin_page))
+        .route("/moderation-page", get(get_moderation_page))
+        .route("/catalog-page", get(get_catalog_page))
         .route("/api/login", post(api_login))
         .route("/api/logout", post(api_logout))
         .route_layer(AuthLayer::new(state.clone()))
@@ -135,6 +149,53 @@ async fn get_admin_page(_login_info: LoginInfoExtractor<LoginInfo>) -> &'static
     "admin-page"
 }
 
+struct AdminGuard;
+
+impl RoleGuard<LoginInfo> for AdminGuard {
+    fn authorize(login_info: &LoginInfo) -> bool {
+        login_info.role == "admin"
+    }
+}
+
+async fn get_guarded_admin_page(_login_info: Authorized<LoginInfo, AdminGuard>) -> &'static str {
+    "guarded-admin-page"
+}
+
+struct StaffRoles;
+
+impl Roles<LoginInfo> for StaffRoles {
+    const ALLOWED_ROLES: &'static [&'static str] = &["admin", "moderator"];
+
+    fn role_of(login_info: &LoginInfo) -> &str {
+        &login_info.role
+    }
+}
+
+async fn get_moderation_page(_login_info: RequireRoleIn<LoginInfo, StaffRoles>) -> &'static str {
+    "moderation-page"
+}
+
+struct CatalogRoles;
+
+impl Roles<LoginInfo> for CatalogRoles {
+    const ALLOWED_ROLES: &'static [&'static str] = &["guest", "regular"];
+
+    fn role_of(login_info: &LoginInfo) -> &str {
+        &login_info.role
+    }
+
+    fn anonymous() -> Option<LoginInfo> {
+        Some(LoginInfo {
+            loginname: "guest".into(),
+            role: "guest".into(),
+        })
+    }
+}
+
+async fn get_catalog_page(login_info: RequireRoleIn<LoginInfo, CatalogRoles>) -> String {
+    format!("catalog-page of {}", login_info.login_info().loginname)
+}
+
 #[derive(Clone)]
 struct LoginInfo {
     loginname: String,
@@ -158,7 +219,7 @@ async fn api_login(
 ) -> Result<(StatusCode, AccessTokenResponse, Json<LoginResponse>), StatusCode> {
     let (access_token_response, _login_info) = state
         .login(&login_request.loginname, login_request.password)
-        .ok_or_else(|| StatusCode::BAD_REQUEST)?;
+        .ok_or(StatusCode::BAD_REQUEST)?;
 
     log::info!("User logged in, loginname = '{}'", login_request.loginname);
 
@@ -179,29 +240,151 @@ async fn api_logout(
 
 #[tokio::test]
 async fn get_page_with_access_policy() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "admin".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/admin-page").await;
+    response.assert_status_ok();
+    response.assert_text("admin-page");
+}
+
+#[tokio::test]
+async fn get_page_with_incorrect_access_policy() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "roger".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/admin-page").await;
+    response.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn get_guarded_page_with_access_policy() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "admin".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/guarded-admin-page").await;
+    response.assert_status_ok();
+    response.assert_text("guarded-admin-page");
+}
+
+#[tokio::test]
+async fn get_guarded_page_with_incorrect_access_policy() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "roger".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/guarded-admin-page").await;
+    response.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn get_guarded_page_unauthenticated() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/guarded-admin-page").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn get_page_as_banned_user() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "banned".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/guarded-admin-page").await;
+    response.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn get_page_with_one_of_the_allowed_roles() {
+    for loginname in ["admin", "moderator"] {
+        let app = AxumApp::new(routes(AppState::new()));
+        let server = login_test_server(
+            app.spawn_test_server().unwrap(),
+            "/api/login",
+            &LoginRequest {
+                loginname: loginname.into(),
+                password: "password".into(),
+            },
+        )
+        .await;
+
+        let response = server.get("/moderation-page").await;
+        response.assert_status_ok();
+        response.assert_text("moderation-page");
+    }
+}
+
+#[tokio::test]
+async fn get_page_without_an_allowed_role() {
     let app = AxumApp::new(routes(AppState::new()));
     let mut server = app.spawn_test_server().unwrap();
     server.do_save_cookies();
 
+    let response = server.get("/moderation-page").await;
+    response.assert_status_unauthorized();
+
     server
         .post("/api/login")
         .json(&LoginRequest {
-            loginname: "admin".into(),
+            loginname: "roger".into(),
             password: "password".into(),
         })
         .await;
 
-    let response = server.get("/admin-page").await;
-    response.assert_status_ok();
-    response.assert_text("admin-page");
+    let response = server.get("/moderation-page").await;
+    response.assert_status_forbidden();
 }
 
 #[tokio::test]
-async fn get_page_with_incorrect_access_policy() {
+async fn get_page_with_anonymous_role() {
     let app = AxumApp::new(routes(AppState::new()));
     let mut server = app.spawn_test_server().unwrap();
     server.do_save_cookies();
 
+    let response = server.get("/catalog-page").await;
+    response.assert_status_ok();
+    response.assert_text("catalog-page of guest");
+
     server
         .post("/api/login")
         .json(&LoginRequest {
@@ -210,6 +393,24 @@ async fn get_page_with_incorrect_access_policy() {
         })
         .await;
 
-    let response = server.get("/admin-page").await;
+    let response = server.get("/catalog-page").await;
+    response.assert_status_ok();
+    response.assert_text("catalog-page of roger");
+}
+
+#[tokio::test]
+async fn get_page_with_anonymous_role_as_disallowed_user() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let server = login_test_server(
+        app.spawn_test_server().unwrap(),
+        "/api/login",
+        &LoginRequest {
+            loginname: "admin".into(),
+            password: "password".into(),
+        },
+    )
+    .await;
+
+    let response = server.get("/catalog-page").await;
     response.assert_status_forbidden();
 }
@@ -0,0 +1,109 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthLayer, AuthLogoutResponse, Cookie, FnAuthHandler,
+        LoginInfoExtractor,
+    },
+    tests::support::{renewing_stub_auth_handler, ACCESS_TOKEN_EXPIRATION_TIME_DURATION},
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    renewing_stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    let api_routes = Router::new()
+        .route("/private", get(get_private))
+        .route("/login", post(api_login))
+        .route("/login-admin", post(api_login_admin))
+        .route("/logout", post(api_logout))
+        .route_layer(AuthLayer::new(state.clone()).with_base_path("/api/"))
+        .with_state(state);
+
+    Router::new().nest("/api", api_routes)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn api_login() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta(
+        AccessToken::new("access-token".into()),
+        ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+        None,
+    )
+}
+
+async fn api_login_admin() -> AccessTokenResponse {
+    api_login().await.with_path("/admin")
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::with_paths(["/", "/admin"], ["/"])
+}
+
+fn set_cookies(response: &axum_test::TestResponse) -> Vec<Cookie<'static>> {
+    response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|header_value| Cookie::parse(header_value.to_str().unwrap().to_string()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn login_cookie_scoped_to_base_path() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    assert_eq!(response.cookie("access_token").path(), Some("/api"));
+
+    let response = server.post("/api/login-admin").await;
+    assert_eq!(response.cookie("access_token").path(), Some("/api/admin"));
+}
+
+#[tokio::test]
+async fn renewed_cookie_scoped_to_base_path() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/api/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("access_token").path(), Some("/api"));
+}
+
+#[tokio::test]
+async fn logout_clears_cookies_under_base_path() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/logout")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    let cookies = set_cookies(&response);
+    let cleared_paths = |name: &str| {
+        cookies
+            .iter()
+            .filter(|cookie| cookie.name() == name)
+            .map(|cookie| cookie.path().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(cleared_paths("access_token"), vec!["/api", "/api/admin"]);
+    assert_eq!(cleared_paths("refresh_token"), vec!["/api"]);
+}
@@ -0,0 +1,58 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use tower::Service;
+
+use crate::{app::AxumApp, body_limit::BodyLimitLayer};
+
+const MAX_BYTES: usize = 16;
+
+fn routes() -> Router {
+    Router::new()
+        .route("/upload", post(post_upload))
+        .route_layer(BodyLimitLayer::new(MAX_BYTES))
+}
+
+async fn post_upload(body: Bytes) -> String {
+    body.len().to_string()
+}
+
+#[tokio::test]
+async fn body_within_limit() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES].into())
+        .await;
+    response.assert_status_ok();
+    response.assert_text(MAX_BYTES.to_string());
+}
+
+#[tokio::test]
+async fn content_length_over_limit() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES + 1].into())
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn body_without_content_length_over_limit() {
+    let request = Request::post("/upload")
+        .body(Body::from(vec![0; MAX_BYTES + 1]))
+        .unwrap();
+    assert!(request.headers().get("content-length").is_none());
+
+    let response = routes().call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,67 @@
+use axum::{http::StatusCode, routing::post, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, AuthLogoutResponse, FnAuthHandler, RenewalDecision},
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes() -> Router {
+    Router::new()
+        .route("/logout", post(api_logout))
+        .route("/logout-clear-site-data", post(api_logout_clear_site_data))
+        .route_layer(AuthLayer::new(auth_handler()))
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+}
+
+async fn api_logout_clear_site_data() -> AuthLogoutResponse {
+    api_logout().await.clear_site_data(["cookies", "storage"])
+}
+
+#[tokio::test]
+async fn clear_site_data_on_logout() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/logout-clear-site-data")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    response.assert_header("clear-site-data", r#""cookies", "storage""#);
+    assert_eq!(response.cookie("access_token").value(), "");
+}
+
+#[tokio::test]
+async fn no_clear_site_data_by_default() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/logout")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    assert!(response.maybe_header("clear-site-data").is_none());
+}
@@ -0,0 +1,48 @@
+use axum::{routing::get, Router};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/", get(get_index))
+}
+
+async fn get_index() -> String {
+    "index".repeat(100)
+}
+
+#[tokio::test]
+async fn compression_off_by_default() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").add_header("accept-encoding", "gzip").await;
+    response.assert_text("index".repeat(100));
+    assert!(response.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn compress_with_accepted_encoding() {
+    let app = AxumApp::new(routes()).with_compression();
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").add_header("accept-encoding", "gzip").await;
+    response.assert_status_ok();
+    assert_eq!(response.header("content-encoding"), "gzip");
+
+    let response = server
+        .get("/")
+        .add_header("accept-encoding", "deflate")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("content-encoding"), "deflate");
+}
+
+#[tokio::test]
+async fn no_compression_without_accept_encoding() {
+    let app = AxumApp::new(routes()).with_compression();
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index".repeat(100));
+    assert!(response.headers().get("content-encoding").is_none());
+}
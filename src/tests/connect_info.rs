@@ -0,0 +1,51 @@
+use std::{net::SocketAddr, time::Duration};
+
+use axum::{extract::ConnectInfo, routing::get, Router};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/peer", get(get_peer))
+}
+
+async fn get_peer(ConnectInfo(peer_address): ConnectInfo<SocketAddr>) -> String {
+    peer_address.to_string()
+}
+
+#[tokio::test]
+async fn test_server_with_connect_info() {
+    let app = AxumApp::new(routes()).with_connect_info();
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/peer").await;
+    response.assert_status_ok();
+    let peer_address: SocketAddr = response.text().parse().unwrap();
+    assert!(peer_address.ip().is_loopback());
+}
+
+#[tokio::test]
+async fn spawned_server_with_connect_info() {
+    let mut app = AxumApp::new(routes()).with_connect_info();
+    let (local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut stream = TcpStream::connect(local_address).await.unwrap();
+    stream
+        .write_all(b"GET /peer HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.ends_with(&stream.local_addr().unwrap().to_string()));
+
+    app.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("server should stop");
+}
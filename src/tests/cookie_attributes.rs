@@ -0,0 +1,529 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthLayer, AuthLogoutResponse, AuthSessionResponse,
+        Cookie, FnAuthHandler, LoginInfoExtractor, RefreshToken, RefreshTokenResponse, SameSite,
+    },
+    tests::support::{
+        renewing_stub_auth_handler, stub_auth_handler, ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+    },
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/login", post(api_login))
+        .route("/api/login-cross-site", post(api_login_cross_site))
+        .route("/api/login-app-path", post(api_login_app_path))
+        .route("/api/logout", post(api_logout))
+        .route("/api/logout-every-path", post(api_logout_every_path))
+        .route("/api/logout-cross-site", post(api_logout_cross_site))
+        .route_layer(AuthLayer::new(state.clone()))
+        .with_state(state)
+}
+
+fn insecure_routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/login-cross-site", post(api_login_cross_site))
+        .route("/api/logout-cross-site", post(api_logout_cross_site))
+        .route(
+            "/api/logout-insecure-cross-site",
+            post(api_logout_insecure_cross_site),
+        )
+        .route_layer(AuthLayer::new(state.clone()).with_secure_cookies(false))
+        .with_state(state)
+}
+
+fn host_prefixed_routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/api/login", post(api_login))
+        .route("/api/login-at-api-path", post(api_login_at_api_path))
+        .route("/api/login-with-domain", post(api_login_with_domain))
+        .route("/api/logout-every-path", post(api_logout_every_path))
+        .route_layer(AuthLayer::new(state.clone()).with_host_cookie_prefix(true))
+        .with_state(state)
+}
+
+fn partitioned_routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/login-session", post(api_login_session))
+        .route_layer(AuthLayer::new(state.clone()).with_partitioned_cookies(true))
+        .with_state(state)
+}
+
+/// Routes whose auth layer renews the access token on every request.
+fn renewing_routes(
+    auth_layer: impl Fn(FnAuthHandler<LoginInfo>) -> AuthLayer<LoginInfo, FnAuthHandler<LoginInfo>>,
+) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/api/login", post(api_login))
+        .route("/api/login-with-domain", post(api_login_with_domain))
+        .route("/api/logout-at-layer-path", post(api_logout_at_layer_path))
+        .route("/api/logout-with-domain", post(api_logout_with_domain))
+        .route_layer(auth_layer(renewing_stub_auth_handler(|_access_token| {
+            Ok(LoginInfo)
+        })))
+}
+
+fn browser_routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/login-session", post(api_login_session))
+        .route("/api/login-cross-site", post(api_login_cross_site))
+        .route_layer(AuthLayer::recommended_browser_defaults(state.clone()))
+        .with_state(state)
+}
+
+async fn api_login_session() -> AuthSessionResponse {
+    AuthSessionResponse::new(
+        access_token_response(),
+        RefreshTokenResponse::with_time_delta(
+            RefreshToken::new("refresh-token".into()),
+            ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+            "/",
+        ),
+    )
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+}
+
+async fn api_logout_every_path() -> AuthLogoutResponse {
+    AuthLogoutResponse::with_paths(["/", "/api"], ["/", "/api/refresh-login"])
+}
+
+async fn api_logout_at_layer_path() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(None::<String>, None::<String>)
+}
+
+async fn api_logout_with_domain() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(None::<String>, Some("/")).with_domain("example.com")
+}
+
+async fn api_logout_cross_site() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/")).same_site(SameSite::None)
+}
+
+async fn api_logout_insecure_cross_site() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+        .same_site(SameSite::None)
+        .secure(false)
+}
+
+fn set_cookies(response: &axum_test::TestResponse) -> Vec<Cookie<'static>> {
+    response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|header_value| Cookie::parse(header_value.to_str().unwrap().to_string()).unwrap())
+        .collect()
+}
+
+fn cleared_cookie_paths(cookies: &[Cookie<'static>], name: &str) -> Vec<String> {
+    cookies
+        .iter()
+        .filter(|cookie| cookie.name() == name)
+        .inspect(|cookie| assert_eq!(cookie.value(), ""))
+        .map(|cookie| cookie.path().unwrap().to_string())
+        .collect()
+}
+
+fn access_token_response() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta(
+        AccessToken::new("access-token".into()),
+        ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+        None,
+    )
+}
+
+async fn api_login() -> AccessTokenResponse {
+    access_token_response()
+}
+
+async fn api_login_at_api_path() -> AccessTokenResponse {
+    AccessTokenResponse::with_time_delta(
+        AccessToken::new("access-token".into()),
+        ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+        Some("/api"),
+    )
+}
+
+async fn api_login_cross_site() -> AccessTokenResponse {
+    access_token_response().same_site(SameSite::None)
+}
+
+async fn api_login_app_path() -> AccessTokenResponse {
+    access_token_response()
+        .with_path("/app")
+        .with_domain("example.com")
+        .same_site(SameSite::Lax)
+}
+
+async fn api_login_with_domain() -> AccessTokenResponse {
+    access_token_response().with_domain("example.com")
+}
+
+#[tokio::test]
+async fn access_token_cookie_same_site_strict_by_default() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+    assert_eq!(cookie.secure(), Some(true));
+}
+
+#[tokio::test]
+async fn access_token_cookie_same_site_none() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-cross-site").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.same_site(), Some(SameSite::None));
+    assert_eq!(cookie.secure(), Some(true));
+}
+
+#[tokio::test]
+async fn same_site_none_cookies_stay_secure_without_secure_cookies() {
+    let app = AxumApp::new(insecure_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-cross-site").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.same_site(), Some(SameSite::None));
+    assert_eq!(cookie.secure(), Some(true));
+
+    for logout_path in ["/api/logout-cross-site", "/api/logout-insecure-cross-site"] {
+        let response = server.post(logout_path).await;
+        let cookies = set_cookies(&response);
+        assert_eq!(cookies.len(), 2);
+        for cookie in &cookies {
+            assert_eq!(cookie.same_site(), Some(SameSite::None));
+            assert_eq!(cookie.secure(), Some(true));
+        }
+    }
+}
+
+#[tokio::test]
+async fn access_token_cookie_path_and_domain() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.path(), Some("/"));
+    assert_eq!(cookie.domain(), None);
+
+    let response = server.post("/api/login-app-path").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.path(), Some("/app"));
+    assert_eq!(cookie.domain(), Some("example.com"));
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+}
+
+#[tokio::test]
+async fn logout_clears_both_cookies() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/logout").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(cleared_cookie_paths(&cookies, "access_token"), vec!["/"]);
+    assert_eq!(cleared_cookie_paths(&cookies, "refresh_token"), vec!["/"]);
+}
+
+#[tokio::test]
+async fn logout_clears_cookies_with_matching_attributes() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/logout").await;
+    for cookie in set_cookies(&response) {
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+        assert_eq!(cookie.secure(), Some(true));
+    }
+
+    let response = server.post("/api/logout-cross-site").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(cookies.len(), 2);
+    for cookie in cookies {
+        assert_eq!(cookie.value(), "");
+        assert_eq!(cookie.same_site(), Some(SameSite::None));
+        assert_eq!(cookie.secure(), Some(true));
+    }
+}
+
+#[tokio::test]
+async fn logout_clears_cookies_at_every_path() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/logout-every-path").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(
+        cleared_cookie_paths(&cookies, "access_token"),
+        vec!["/", "/api"]
+    );
+    assert_eq!(
+        cleared_cookie_paths(&cookies, "refresh_token"),
+        vec!["/", "/api/refresh-login"]
+    );
+}
+
+#[tokio::test]
+async fn host_prefixed_access_token_cookie() {
+    let app = AxumApp::new(host_prefixed_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    assert!(response.maybe_cookie("access_token").is_none());
+    let cookie = response.cookie("__Host-access_token");
+    assert_eq!(cookie.secure(), Some(true));
+    assert_eq!(cookie.path(), Some("/"));
+    assert_eq!(cookie.domain(), None);
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "__Host-access_token=access-token")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn host_prefixed_access_token_cookie_with_other_path() {
+    let app = AxumApp::new(host_prefixed_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-at-api-path").await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("__Host-access_token").path(), Some("/"));
+}
+
+#[tokio::test]
+async fn host_prefixed_access_token_cookie_with_domain() {
+    let app = AxumApp::new(host_prefixed_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-with-domain").await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("__Host-access_token").domain(), None);
+}
+
+#[tokio::test]
+async fn host_prefixed_access_token_cookie_cleared_at_root_path() {
+    let app = AxumApp::new(host_prefixed_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/logout-every-path").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(
+        cleared_cookie_paths(&cookies, "__Host-access_token"),
+        vec!["/"]
+    );
+    assert!(cleared_cookie_paths(&cookies, "access_token").is_empty());
+}
+
+#[tokio::test]
+async fn partitioned_cookies() {
+    let app = AxumApp::new(partitioned_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-session").await;
+    let set_cookie_header_values = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|header_value| header_value.to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(set_cookie_header_values.len(), 2);
+    for set_cookie_header_value in set_cookie_header_values {
+        assert!(set_cookie_header_value.contains("Partitioned"));
+        assert!(set_cookie_header_value.contains("Secure"));
+        assert!(set_cookie_header_value.contains("SameSite=None"));
+    }
+}
+
+#[tokio::test]
+async fn recommended_browser_defaults_lax_access_token_cookie() {
+    let app = AxumApp::new(browser_routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-session").await;
+    assert_eq!(
+        response.cookie("access_token").same_site(),
+        Some(SameSite::Lax)
+    );
+    assert_eq!(
+        response.cookie("refresh_token").same_site(),
+        Some(SameSite::Strict)
+    );
+
+    let response = server.post("/api/login-cross-site").await;
+    assert_eq!(
+        response.cookie("access_token").same_site(),
+        Some(SameSite::None)
+    );
+}
+
+#[tokio::test]
+async fn renewed_access_token_cookie_keeps_same_site() {
+    let app = AxumApp::new(renewing_routes(|auth_handler| {
+        AuthLayer::new(auth_handler).with_access_token_same_site(SameSite::None)
+    }));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    assert_eq!(
+        response.cookie("access_token").same_site(),
+        Some(SameSite::None)
+    );
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.value(), "access-token");
+    assert_eq!(cookie.same_site(), Some(SameSite::None));
+}
+
+#[tokio::test]
+async fn recommended_browser_defaults_renew_lax_access_token_cookie() {
+    let app = AxumApp::new(renewing_routes(AuthLayer::recommended_browser_defaults));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    assert_eq!(
+        response.cookie("access_token").same_site(),
+        Some(SameSite::Lax)
+    );
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.value(), "access-token");
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+}
+
+#[tokio::test]
+async fn domain_cookie_keeps_path_and_domain_through_renewal_and_logout() {
+    let app = AxumApp::new(renewing_routes(|auth_handler| {
+        AuthLayer::new(auth_handler)
+            .with_access_token_path("/private")
+            .with_cookie_domain("example.com")
+    }));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.path(), Some("/private"));
+    assert_eq!(cookie.domain(), Some("example.com"));
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.value(), "access-token");
+    assert_eq!(cookie.path(), Some("/private"));
+    assert_eq!(cookie.domain(), Some("example.com"));
+
+    let response = server.post("/api/logout-at-layer-path").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(
+        cleared_cookie_paths(&cookies, "access_token"),
+        vec!["/private".to_string()]
+    );
+    for cookie in &cookies {
+        assert_eq!(cookie.domain(), Some("example.com"));
+    }
+}
+
+#[tokio::test]
+async fn logout_clears_domain_cookie() {
+    let app = AxumApp::new(renewing_routes(AuthLayer::new));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login-with-domain").await;
+    assert_eq!(
+        response.cookie("access_token").domain(),
+        Some("example.com")
+    );
+
+    let response = server.post("/api/logout-with-domain").await;
+    let cookies = set_cookies(&response);
+    assert_eq!(
+        cleared_cookie_paths(&cookies, "access_token"),
+        vec!["/".to_string()]
+    );
+    for cookie in &cookies {
+        assert_eq!(cookie.domain(), Some("example.com"));
+    }
+}
+
+#[tokio::test]
+async fn renewed_and_cleared_cookies_keep_layer_attributes() {
+    let app = AxumApp::new(renewing_routes(|auth_handler| {
+        AuthLayer::new(auth_handler)
+            .with_access_token_same_site(SameSite::Lax)
+            .with_secure_cookies(false)
+    }));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_ne!(cookie.secure(), Some(true));
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    let cookie = response.cookie("access_token");
+    assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    assert_ne!(cookie.secure(), Some(true));
+
+    let response = server.post("/api/logout-at-layer-path").await;
+    let cookies = set_cookies(&response);
+    for cookie in &cookies {
+        assert_ne!(cookie.secure(), Some(true));
+    }
+    let access_token_cookie = cookies
+        .iter()
+        .find(|cookie| cookie.name() == "access_token")
+        .unwrap();
+    assert_eq!(access_token_cookie.same_site(), Some(SameSite::Lax));
+}
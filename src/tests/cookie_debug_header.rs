@@ -0,0 +1,86 @@
+use axum::{http::StatusCode, routing::post, Router};
+use time::OffsetDateTime;
+
+use crate::{
+    app::AxumApp,
+    auth::{AccessTokenResponse, AuthLayer, FnAuthHandler, RefreshTokenResponse, RenewalDecision},
+};
+
+const ACCESS_TOKEN: &str = "secret-access-token";
+const REFRESH_TOKEN: &str = "secret-refresh-token";
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |_access_token| Box::pin(async { Err(StatusCode::UNAUTHORIZED) }),
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes(auth_layer: AuthLayer<LoginInfo, FnAuthHandler<LoginInfo>>) -> Router {
+    Router::new()
+        .route("/api/login", post(api_login))
+        .route_layer(auth_layer)
+}
+
+async fn api_login() -> (AccessTokenResponse, RefreshTokenResponse) {
+    (
+        AccessTokenResponse::with_offset_date_time(
+            ACCESS_TOKEN,
+            OffsetDateTime::from_unix_timestamp(4_000_000_000).unwrap(),
+            None,
+        ),
+        RefreshTokenResponse::with_offset_date_time(
+            REFRESH_TOKEN,
+            OffsetDateTime::from_unix_timestamp(4_100_000_000).unwrap(),
+            "/api/refresh-login",
+        ),
+    )
+}
+
+fn cookie_debug_header_values(response: &axum_test::TestResponse) -> Vec<String> {
+    response
+        .headers()
+        .get_all("x-auth-cookie-debug")
+        .iter()
+        .map(|header_value| header_value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn cookie_debug_header() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(auth_handler()).with_cookie_debug_header(true),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    response.assert_status_ok();
+
+    let cookie_debug_header_values = cookie_debug_header_values(&response);
+    assert_eq!(cookie_debug_header_values.len(), 2);
+    assert!(cookie_debug_header_values.contains(
+        &"access_token; Path=/; SameSite=Strict; Secure; HttpOnly; Expires=4000000000".to_string()
+    ));
+    assert!(cookie_debug_header_values.contains(
+        &"refresh_token; Path=/api/refresh-login; SameSite=Strict; Secure; HttpOnly; Expires=4100000000"
+            .to_string()
+    ));
+    for cookie_debug_header_value in &cookie_debug_header_values {
+        assert!(!cookie_debug_header_value.contains(ACCESS_TOKEN));
+        assert!(!cookie_debug_header_value.contains(REFRESH_TOKEN));
+    }
+}
+
+#[tokio::test]
+async fn cookie_debug_header_disabled_by_default() {
+    let app = AxumApp::new(routes(AuthLayer::new(auth_handler())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/login").await;
+    response.assert_status_ok();
+    assert!(cookie_debug_header_values(&response).is_empty());
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, SystemTime};
+
+use time::OffsetDateTime;
+
+use crate::auth::{is_cookie_expired_at, is_cookie_expired_by_date, Cookie};
+
+fn cookie_expiring_at(expires_at: SystemTime) -> Cookie<'static> {
+    Cookie::build(("name", "value"))
+        .expires(OffsetDateTime::from(expires_at))
+        .build()
+}
+
+#[test]
+fn cookie_expiring_exactly_now() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let cookie = cookie_expiring_at(now);
+
+    assert!(!is_cookie_expired_at(&cookie, now));
+}
+
+#[test]
+fn cookie_expired_one_second_ago() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let cookie = cookie_expiring_at(now - Duration::from_secs(1));
+
+    assert!(is_cookie_expired_at(&cookie, now));
+}
+
+#[test]
+fn cookie_expiring_in_one_second() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let cookie = cookie_expiring_at(now + Duration::from_secs(1));
+
+    assert!(!is_cookie_expired_at(&cookie, now));
+}
+
+#[test]
+fn cookie_expired_by_date() {
+    let cookie = cookie_expiring_at(SystemTime::now() - Duration::from_secs(1));
+
+    assert!(is_cookie_expired_by_date(&cookie));
+}
+
+#[test]
+fn cookie_without_expiry_never_expires() {
+    let cookie = Cookie::new("name", "value");
+
+    assert!(!is_cookie_expired_at(&cookie, SystemTime::UNIX_EPOCH));
+    assert!(!is_cookie_expired_at(
+        &cookie,
+        SystemTime::now() + Duration::from_secs(100 * 365 * 24 * 60 * 60)
+    ));
+    assert!(!is_cookie_expired_by_date(&cookie));
+}
+
+#[test]
+fn request_cookie_has_no_expiry() {
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("cookie", "access_token=token".parse().unwrap());
+    let cookie_jar = axum_extra::extract::CookieJar::from_headers(&headers);
+    let cookie = cookie_jar.get("access_token").unwrap();
+
+    assert_eq!(cookie.expires(), None);
+    assert!(!is_cookie_expired_by_date(cookie));
+}
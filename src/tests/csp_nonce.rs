@@ -0,0 +1,53 @@
+use axum::{http::header::CONTENT_SECURITY_POLICY, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    csp_nonce::{CspNonce, CspNonceLayer},
+};
+
+fn routes() -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route("/own-policy", get(get_own_policy))
+        .route_layer(CspNonceLayer::new("default-src 'self'; script-src 'self'"))
+}
+
+async fn get_index(CspNonce(nonce): CspNonce) -> String {
+    nonce
+}
+
+async fn get_own_policy(
+    CspNonce(nonce): CspNonce,
+) -> ([(axum::http::HeaderName, &'static str); 1], String) {
+    ([(CONTENT_SECURITY_POLICY, "default-src 'none'")], nonce)
+}
+
+#[tokio::test]
+async fn nonce_added_to_policy() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    let nonce = response.text();
+    assert!(!nonce.is_empty());
+    assert_eq!(
+        response.header(CONTENT_SECURITY_POLICY),
+        format!("default-src 'self'; script-src 'self' 'nonce-{nonce}'")
+    );
+
+    let response = server.get("/").await;
+    assert_ne!(response.text(), nonce);
+}
+
+#[tokio::test]
+async fn nonce_added_to_policy_of_request_handler() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/own-policy").await;
+    let nonce = response.text();
+    assert_eq!(
+        response.header(CONTENT_SECURITY_POLICY),
+        format!("default-src 'none'; script-src 'nonce-{nonce}'")
+    );
+}
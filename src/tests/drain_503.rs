@@ -0,0 +1,36 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/", get(get_index))
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[tokio::test]
+async fn reject_requests_after_stop() {
+    let mut app = AxumApp::new(routes()).with_drain_503();
+    let server = app.spawn_test_server().unwrap();
+
+    server.get("/").await.assert_text("index");
+
+    app.stop_server();
+    let response = server.get("/").await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    response.assert_header("connection", "close");
+
+    app.restart();
+    server.get("/").await.assert_text("index");
+}
+
+#[tokio::test]
+async fn serve_requests_after_stop_by_default() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    app.stop_server();
+    server.get("/").await.assert_text("index");
+}
@@ -0,0 +1,56 @@
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/", get(get_index))
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+async fn custom_not_found() -> (StatusCode, &'static str) {
+    (StatusCode::NOT_FOUND, "custom-not-found")
+}
+
+#[tokio::test]
+async fn custom_fallback() {
+    let app = AxumApp::new(routes()).with_fallback(custom_not_found);
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+
+    let response = server.get("/missing").await;
+    response.assert_status_not_found();
+    response.assert_text("custom-not-found");
+}
+
+#[tokio::test]
+async fn json_not_found() {
+    let app = AxumApp::new(routes()).with_json_not_found();
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/missing").await;
+    response.assert_status_not_found();
+    response.assert_json(&serde_json::json!({ "error": "not_found" }));
+
+    let response = server.post("/").await;
+    response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    response.assert_json(&serde_json::json!({ "error": "method_not_allowed" }));
+}
+
+#[tokio::test]
+async fn json_not_found_with_router_factory() {
+    let app = AxumApp::new_with(|| Router::new().route("/", post(get_index))).with_json_not_found();
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    response.assert_json(&serde_json::json!({ "error": "method_not_allowed" }));
+}
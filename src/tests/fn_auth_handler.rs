@@ -0,0 +1,133 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AuthLayer, AuthLogoutResponse, FnAuthHandler, LoginInfoExtractor, RefreshTokenExtractor,
+        RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+}
+
+fn auth_handler(access_token_revoked: Arc<AtomicBool>) -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                access_token
+                    .strip_prefix("token-of-")
+                    .map(|loginname| LoginInfo {
+                        loginname: loginname.to_string(),
+                    })
+                    .ok_or(StatusCode::UNAUTHORIZED)
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        move |_access_token, _login_info| {
+            let access_token_revoked = access_token_revoked.clone();
+            Box::pin(async move { access_token_revoked.store(true, Ordering::SeqCst) })
+        },
+    )
+}
+
+fn routes(auth_handler: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/api/logout", post(api_logout))
+        .route("/api/refresh-login", post(api_refresh_login))
+        .route_layer(AuthLayer::new(auth_handler))
+}
+
+async fn get_private(LoginInfoExtractor(login_info): LoginInfoExtractor<LoginInfo>) -> String {
+    login_info.loginname.clone()
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+}
+
+async fn api_refresh_login(
+    RefreshTokenExtractor(_refresh_token): RefreshTokenExtractor,
+) -> &'static str {
+    "refreshed"
+}
+
+#[tokio::test]
+async fn access_token_closures() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(auth_handler(access_token_revoked.clone())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("user");
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=invalid-token")
+        .await;
+    response.assert_status_unauthorized();
+
+    server
+        .post("/api/logout")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    assert!(access_token_revoked.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn refresh_token_rejected_by_default() {
+    let app = AxumApp::new(routes(auth_handler(Arc::new(AtomicBool::new(false)))));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=refresh-token")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn refresh_token_closures() {
+    let auth_handler = auth_handler(Arc::new(AtomicBool::new(false))).with_refresh_token_fns(
+        |refresh_token| {
+            Box::pin(async move {
+                if refresh_token.as_str() == "refresh-token" {
+                    Ok(())
+                } else {
+                    Err(StatusCode::BAD_REQUEST)
+                }
+            })
+        },
+        |_refresh_token| Box::pin(async {}),
+    );
+    let app = AxumApp::new(routes(auth_handler));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=refresh-token")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=other-refresh-token")
+        .await;
+    response.assert_status_bad_request();
+}
@@ -0,0 +1,168 @@
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, FormCsrf, FormCsrfLayer, RenewalDecision},
+};
+
+const CSRF_KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.starts_with("valid") {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes(form_csrf_layer: FormCsrfLayer) -> Router {
+    Router::new()
+        .route("/form", get(get_form))
+        .route("/submit", post(post_submit))
+        .route_layer(form_csrf_layer)
+        .route_layer(AuthLayer::new(auth_handler()))
+}
+
+async fn get_form(FormCsrf(csrf_token): FormCsrf) -> String {
+    csrf_token
+}
+
+async fn post_submit(body: String) -> String {
+    body
+}
+
+async fn fetch_csrf_token(server: &axum_test::TestServer, access_token: &str) -> String {
+    let response = server
+        .get("/form")
+        .add_header("cookie", format!("access_token={access_token}"))
+        .await;
+    response.assert_status_ok();
+    response.text()
+}
+
+#[tokio::test]
+async fn csrf_token_stable_for_access_token() {
+    let app = AxumApp::new(routes(FormCsrfLayer::new(CSRF_KEY)));
+    let server = app.spawn_test_server().unwrap();
+
+    let csrf_token = fetch_csrf_token(&server, "valid-1").await;
+    assert_eq!(csrf_token.len(), 64);
+    assert_eq!(fetch_csrf_token(&server, "valid-1").await, csrf_token);
+    assert_ne!(fetch_csrf_token(&server, "valid-2").await, csrf_token);
+
+    let response = server.get("/form").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn accept_form_with_valid_csrf_token() {
+    let app = AxumApp::new(routes(FormCsrfLayer::new(CSRF_KEY)));
+    let server = app.spawn_test_server().unwrap();
+
+    let body = format!(
+        "name=value&_csrf={}",
+        fetch_csrf_token(&server, "valid-1").await
+    );
+    let response = server
+        .post("/submit")
+        .add_header("cookie", "access_token=valid-1")
+        .add_header("content-type", FORM_CONTENT_TYPE)
+        .bytes(body.clone().into())
+        .await;
+    response.assert_status_ok();
+    response.assert_text(body);
+}
+
+#[tokio::test]
+async fn reject_form_without_valid_csrf_token() {
+    let app = AxumApp::new(routes(FormCsrfLayer::new(CSRF_KEY)));
+    let server = app.spawn_test_server().unwrap();
+
+    let other_csrf_token = fetch_csrf_token(&server, "valid-2").await;
+    for body in [
+        "name=value".to_string(),
+        "name=value&_csrf=00".to_string(),
+        format!("name=value&_csrf={other_csrf_token}"),
+    ] {
+        let response = server
+            .post("/submit")
+            .add_header("cookie", "access_token=valid-1")
+            .add_header("content-type", FORM_CONTENT_TYPE)
+            .bytes(body.into())
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    let body = format!("_csrf={}", fetch_csrf_token(&server, "valid-1").await);
+    let response = server
+        .post("/submit")
+        .add_header("cookie", "access_token=valid-1")
+        .add_header("content-type", "text/plain")
+        .bytes(body.into())
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn pass_requests_not_checked() {
+    let app = AxumApp::new(routes(FormCsrfLayer::new(CSRF_KEY)));
+    let server = app.spawn_test_server().unwrap();
+
+    // no session to forge a request for
+    let response = server
+        .post("/submit")
+        .add_header("content-type", FORM_CONTENT_TYPE)
+        .bytes("name=value".into())
+        .await;
+    response.assert_status_ok();
+
+    // not sendable by a cross-site form
+    let response = server
+        .post("/submit")
+        .add_header("cookie", "access_token=valid-1")
+        .add_header("content-type", "application/json")
+        .bytes(r#"{"name":"value"}"#.into())
+        .await;
+    response.assert_status_ok();
+    response.assert_text(r#"{"name":"value"}"#);
+}
+
+#[tokio::test]
+async fn reject_form_over_body_limit() {
+    let app = AxumApp::new(routes(FormCsrfLayer::new(CSRF_KEY).with_body_limit(128)));
+    let server = app.spawn_test_server().unwrap();
+
+    let csrf_token = fetch_csrf_token(&server, "valid-1").await;
+
+    let response = server
+        .post("/submit")
+        .add_header("cookie", "access_token=valid-1")
+        .add_header("content-type", FORM_CONTENT_TYPE)
+        .bytes(format!("_csrf={csrf_token}").into())
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .post("/submit")
+        .add_header("cookie", "access_token=valid-1")
+        .add_header("content-type", FORM_CONTENT_TYPE)
+        .bytes(format!("_csrf={csrf_token}&name={}", "a".repeat(128)).into())
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+}
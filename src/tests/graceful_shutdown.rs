@@ -0,0 +1,250 @@
+use std::time::Duration;
+
+use axum::{routing::get, Router};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new().route("/slow", get(get_slow))
+}
+
+async fn get_slow() -> &'static str {
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    "slow"
+}
+
+fn free_local_address() -> std::net::SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn drain_deadline_drops_in_flight_requests() {
+    let listener_address = free_local_address();
+
+    let mut app = AxumApp::new(routes());
+    app.set_drain_deadline(Some(Duration::from_millis(100)));
+    app.spawn_server(listener_address).await.unwrap();
+
+    let mut stream = TcpStream::connect(listener_address).await.unwrap();
+    stream
+        .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+
+    // give the server some time to start processing the request
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    app.stop_server();
+
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("server should stop after the drain deadline elapsed");
+
+    // the connection of the in-flight request is closed without a response, i.e., EOF or a reset
+    let mut response = Vec::new();
+    let read_result =
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut response))
+            .await
+            .expect("connection should be closed after the drain deadline elapsed");
+    assert!(read_result.is_err() || response.is_empty());
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[tokio::test]
+async fn shutdown_future_stops_server() {
+    let listener_address = free_local_address();
+
+    let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel::<()>();
+
+    let mut app = AxumApp::new(Router::new().route("/", get(get_index)));
+    app.spawn_server_with_shutdown(listener_address, async move {
+        let _ = shutdown_receiver.await;
+    })
+    .await
+    .unwrap();
+
+    TcpStream::connect(listener_address).await.unwrap();
+
+    let _ = shutdown_sender.send(());
+
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("server should stop when the shutdown future completes");
+}
+
+#[tokio::test]
+async fn subscribe_shutdown_completion() {
+    let listener_address = free_local_address();
+
+    let mut app = AxumApp::new(Router::new().route("/", get(get_index)));
+    app.spawn_server(listener_address).await.unwrap();
+
+    let mut shutdown_complete_receiver = app.subscribe_shutdown();
+    assert!(!*shutdown_complete_receiver.borrow());
+
+    let shutdown_complete = tokio::spawn(async move {
+        shutdown_complete_receiver
+            .wait_for(|shutdown_complete| *shutdown_complete)
+            .await
+            .is_ok()
+    });
+
+    app.stop_server();
+    app.join().await;
+
+    let shutdown_complete = tokio::time::timeout(Duration::from_secs(5), shutdown_complete)
+        .await
+        .expect("shutdown completion should be signaled after join")
+        .unwrap();
+    assert!(shutdown_complete);
+}
+
+#[tokio::test]
+async fn run_server_until_stopped() {
+    let listener_address = free_local_address();
+
+    let app = std::sync::Arc::new(AxumApp::new(Router::new().route("/", get(get_index))));
+    let server = tokio::spawn({
+        let app = app.clone();
+        async move { app.run_server(listener_address).await }
+    });
+
+    // give the server some time to bind the listener
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(TcpStream::connect(listener_address).await.is_ok());
+
+    app.stop_server();
+
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("run_server should return after the server is stopped")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn spawn_server_handle() {
+    let mut app = AxumApp::new(Router::new().route("/", get(get_index)));
+    let (local_address, joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    assert_ne!(local_address.port(), 0);
+    assert!(TcpStream::connect(local_address).await.is_ok());
+    assert!(!joinhandle.is_finished());
+
+    app.stop_server();
+
+    tokio::time::timeout(Duration::from_secs(5), joinhandle)
+        .await
+        .expect("server task should end after the server is stopped")
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("join should return after the server task ended");
+}
+
+async fn get_over_tcp(listener_address: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(listener_address).await.unwrap();
+    stream
+        .write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn restart_stopped_server() {
+    let mut app = AxumApp::new(Router::new().route("/", get(get_index)));
+    let (local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    assert!(get_over_tcp(local_address, "/").await.ends_with("index"));
+
+    app.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("server should stop");
+    assert!(*app.subscribe_shutdown().borrow());
+
+    // without a restart a stopped app does not serve
+    let (_local_address, joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), joinhandle)
+        .await
+        .expect("server spawned after stop_server should stop immediately")
+        .unwrap();
+    app.join().await;
+
+    app.restart();
+    assert!(!*app.subscribe_shutdown().borrow());
+
+    let (local_address, joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    assert!(get_over_tcp(local_address, "/").await.ends_with("index"));
+    assert!(!joinhandle.is_finished());
+
+    app.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("restarted server should stop");
+}
+
+#[tokio::test]
+async fn servers_serve_router_changed_after_spawn() {
+    let mut app = AxumApp::new(Router::new().route("/", get(get_index))).with_json_not_found();
+    app.set_drain_deadline(Some(Duration::from_millis(100)));
+
+    let (first_local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    let (second_local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    for local_address in [first_local_address, second_local_address] {
+        assert!(get_over_tcp(local_address, "/").await.ends_with("index"));
+        assert!(get_over_tcp(local_address, "/new")
+            .await
+            .ends_with(r#"{"error":"not_found"}"#));
+    }
+
+    app.map_router(|router| router.route("/new", get(get_index)));
+    let (third_local_address, _joinhandle) = app
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    assert!(get_over_tcp(third_local_address, "/new")
+        .await
+        .ends_with("index"));
+    assert!(get_over_tcp(first_local_address, "/new")
+        .await
+        .ends_with(r#"{"error":"not_found"}"#));
+
+    app.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("servers should stop");
+}
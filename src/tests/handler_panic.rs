@@ -0,0 +1,128 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{http::StatusCode, routing::get, Router};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{AccessToken, AuthLayer, FnAuthHandler, RenewalDecision, SessionControl},
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct LoginInfo;
+
+/// Accepts the access tokens starting with `valid` and replaces them with `valid-renewed` on
+/// every request, so the old access token is of no use once the renewal was decided.
+fn auth_handler(access_token_revoked: Arc<AtomicBool>) -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.starts_with("valid") {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| {
+            Box::pin(async {
+                RenewalDecision::Renew(
+                    AccessToken::from("valid-renewed"),
+                    ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+                )
+            })
+        },
+        move |_access_token, _login_info| {
+            let access_token_revoked = access_token_revoked.clone();
+            Box::pin(async move { access_token_revoked.store(true, Ordering::SeqCst) })
+        },
+    )
+}
+
+fn routes(access_token_revoked: Arc<AtomicBool>, catch_panic: bool) -> Router {
+    Router::new()
+        .route("/panic", get(get_panic))
+        .route("/logout-then-panic", get(get_logout_then_panic))
+        .route_layer(
+            AuthLayer::new(auth_handler(access_token_revoked)).with_catch_panic(catch_panic),
+        )
+}
+
+async fn get_panic() -> &'static str {
+    panic!("request handler panicked")
+}
+
+async fn get_logout_then_panic(session_control: SessionControl) -> &'static str {
+    session_control.logout();
+    panic!("request handler panicked")
+}
+
+#[tokio::test]
+async fn renew_access_token_despite_panic() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(access_token_revoked, true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/panic")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.cookie("access_token").value(), "valid-renewed");
+}
+
+#[tokio::test]
+async fn logout_requested_before_panic() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(access_token_revoked.clone(), true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/logout-then-panic")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.cookie("access_token").value(), "");
+    assert!(access_token_revoked.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn panic_unwinds_by_default() {
+    let listener_address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let mut app = AxumApp::new(routes(access_token_revoked.clone(), false));
+    app.spawn_server(listener_address).await.unwrap();
+
+    let mut stream = TcpStream::connect(listener_address).await.unwrap();
+    stream
+        .write_all(
+            b"GET /logout-then-panic HTTP/1.1\r\nHost: localhost\r\n\
+              Cookie: access_token=valid\r\n\r\n",
+        )
+        .await
+        .unwrap();
+    let mut response = Vec::new();
+    let _ = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut response)).await;
+    assert!(response.is_empty());
+    assert!(!access_token_revoked.load(Ordering::SeqCst));
+
+    app.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app.join())
+        .await
+        .expect("server should stop");
+}
@@ -0,0 +1,30 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route_layer(axum::middleware::from_fn(
+            |_req: axum::extract::Request, _next: axum::middleware::Next| async {
+                StatusCode::UNAUTHORIZED
+            },
+        ))
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[tokio::test]
+async fn get_health_route() {
+    let app = AxumApp::new(routes()).with_health_route("/health");
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_status_unauthorized();
+
+    let response = server.get("/health").await;
+    response.assert_status_ok();
+    response.assert_text("ok");
+}
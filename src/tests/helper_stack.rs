@@ -0,0 +1,90 @@
+use axum::{
+    body::Bytes,
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    helper_stack::{HelperStack, DEFAULT_RESPONSE_TIME_HEADER_NAME},
+};
+
+const MAX_BYTES: usize = 16;
+
+fn routes(helper_stack: HelperStack) -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route("/upload", post(post_upload))
+        .route_layer(helper_stack.build())
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+async fn post_upload(body: Bytes) -> String {
+    body.len().to_string()
+}
+
+#[tokio::test]
+async fn every_layer_disabled_by_default() {
+    let app = AxumApp::new(routes(HelperStack::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+    assert!(response
+        .headers()
+        .get(DEFAULT_RESPONSE_TIME_HEADER_NAME)
+        .is_none());
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES + 1].into())
+        .await;
+    response.assert_text((MAX_BYTES + 1).to_string());
+}
+
+#[tokio::test]
+async fn enabled_layers() {
+    let app = AxumApp::new(routes(
+        HelperStack::new()
+            .timing()
+            .require_content_type("application/octet-stream")
+            .body_limit(MAX_BYTES),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+    assert!(response
+        .headers()
+        .get(DEFAULT_RESPONSE_TIME_HEADER_NAME)
+        .is_some());
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES].into())
+        .content_type("text/plain")
+        .await;
+    response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert!(response
+        .headers()
+        .get(DEFAULT_RESPONSE_TIME_HEADER_NAME)
+        .is_some());
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES + 1].into())
+        .content_type("application/octet-stream")
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+
+    let response = server
+        .post("/upload")
+        .bytes(vec![0; MAX_BYTES].into())
+        .content_type("application/octet-stream")
+        .await;
+    response.assert_text(MAX_BYTES.to_string());
+}
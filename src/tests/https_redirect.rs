@@ -0,0 +1,95 @@
+use axum::{
+    http::{header::LOCATION, HeaderName, StatusCode},
+    routing::get,
+    Router,
+};
+
+use crate::{app::AxumApp, https_redirect::HttpsRedirectLayer};
+
+fn routes(https_redirect_layer: HttpsRedirectLayer) -> Router {
+    Router::new()
+        .route("/items", get(get_items))
+        .route_layer(https_redirect_layer)
+}
+
+async fn get_items() -> &'static str {
+    "items"
+}
+
+#[tokio::test]
+async fn pass_through_without_forwarded_proto() {
+    let app = AxumApp::new(routes(HttpsRedirectLayer::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/items").await;
+    response.assert_status_ok();
+    response.assert_text("items");
+}
+
+#[tokio::test]
+async fn pass_through_https() {
+    let app = AxumApp::new(routes(HttpsRedirectLayer::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/items")
+        .add_header("x-forwarded-proto", "https")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("items");
+}
+
+#[tokio::test]
+async fn redirect_plaintext() {
+    let app = AxumApp::new(routes(HttpsRedirectLayer::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/items")
+        .add_query_param("page", "2")
+        .add_header("host", "example.com")
+        .add_header("x-forwarded-proto", "http")
+        .await;
+    response.assert_status(StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        response.header(LOCATION),
+        "https://example.com/items?page=2"
+    );
+}
+
+#[tokio::test]
+async fn reject_plaintext() {
+    let app = AxumApp::new(routes(
+        HttpsRedirectLayer::new().with_rejection(StatusCode::BAD_REQUEST),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/items")
+        .add_header("host", "example.com")
+        .add_header("x-forwarded-proto", "http")
+        .await;
+    response.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn custom_header_name() {
+    let app = AxumApp::new(routes(
+        HttpsRedirectLayer::new()
+            .with_header_name(HeaderName::from_static("x-forwarded-scheme"))
+            .with_rejection(StatusCode::FORBIDDEN),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/items")
+        .add_header("x-forwarded-proto", "http")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .get("/items")
+        .add_header("x-forwarded-scheme", "http")
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use axum::{routing::get, Router};
+use tokio::{io::AsyncWriteExt, net::TcpStream};
+
+use crate::app::AxumApp;
+
+fn routes() -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route("/slow", get(get_slow))
+        .route("/panic", get(get_panic))
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+async fn get_slow() -> &'static str {
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    "slow"
+}
+
+async fn get_panic() -> &'static str {
+    panic!("request handler panicked")
+}
+
+fn free_local_address() -> std::net::SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+async fn send_request(listener_address: std::net::SocketAddr, path: &str) -> TcpStream {
+    let mut stream = TcpStream::connect(listener_address).await.unwrap();
+    stream
+        .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+        .await
+        .unwrap();
+
+    // give the server some time to start processing the request
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    stream
+}
+
+#[tokio::test]
+async fn inflight_is_zero_without_gauge() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    server.get("/").await.assert_status_ok();
+    assert_eq!(app.inflight(), 0);
+}
+
+#[tokio::test]
+async fn count_inflight_requests() {
+    let listener_address = free_local_address();
+
+    let mut app = AxumApp::new(routes()).with_inflight_gauge();
+    app.spawn_server(listener_address).await.unwrap();
+    assert_eq!(app.inflight(), 0);
+
+    let stream = send_request(listener_address, "/slow").await;
+    assert_eq!(app.inflight(), 1);
+
+    // closing the connection drops the request
+    drop(stream);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(app.inflight(), 0);
+}
+
+#[tokio::test]
+async fn count_panicked_request_as_completed() {
+    let listener_address = free_local_address();
+
+    let mut app = AxumApp::new(routes()).with_inflight_gauge();
+    app.spawn_server(listener_address).await.unwrap();
+
+    let _stream = send_request(listener_address, "/panic").await;
+    assert_eq!(app.inflight(), 0);
+}
@@ -0,0 +1,86 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{routing::get, Router};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, JwtAuthHandler, LoginInfoExtractor},
+};
+
+const SECRET: &[u8] = b"secret";
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: u64,
+}
+
+fn routes() -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(AuthLayer::new(JwtAuthHandler::<Claims>::new(
+            DecodingKey::from_secret(SECRET),
+            Validation::default(),
+        )))
+}
+
+async fn get_private(LoginInfoExtractor(claims): LoginInfoExtractor<Claims>) -> String {
+    claims.sub.clone()
+}
+
+fn token(secret: &[u8], expires_in_secs: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let claims = Claims {
+        sub: "user".to_string(),
+        exp: now.saturating_add_signed(expires_in_secs),
+    };
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn valid_token() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", format!("access_token={}", token(SECRET, 60)))
+        .await;
+    response.assert_status_ok();
+    response.assert_text("user");
+    assert!(response.maybe_cookie("access_token").is_none());
+}
+
+#[tokio::test]
+async fn invalid_signature() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", format!("access_token={}", token(b"other", 60)))
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn expired_token() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", format!("access_token={}", token(SECRET, -3600)))
+        .await;
+    response.assert_status_unauthorized();
+}
@@ -0,0 +1,95 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AuthLayer, FnAuthHandler, LoginInfoExtractor, LoginInfoExtractorWithError, RenewalDecision,
+    },
+};
+
+/// Login info that is not `Clone`, e.g., a session holding a handle.
+struct Session {
+    loginname: String,
+}
+
+fn auth_handler() -> FnAuthHandler<Session> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                access_token
+                    .strip_prefix("token-of-")
+                    .map(|loginname| Session {
+                        loginname: loginname.to_string(),
+                    })
+                    .ok_or(StatusCode::UNAUTHORIZED)
+            })
+        },
+        |_access_token, _session| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _session| Box::pin(async {}),
+    )
+}
+
+async fn get_private(LoginInfoExtractor(session): LoginInfoExtractor<Session>) -> String {
+    session.loginname.clone()
+}
+
+async fn get_hybrid(
+    LoginInfoExtractorWithError(verification_result): LoginInfoExtractorWithError<Session>,
+) -> String {
+    match verification_result {
+        None => "anonymous".to_string(),
+        Some(Ok(session)) => session.loginname.clone(),
+        Some(Err(status_code)) => format!("token rejected: {}", status_code.as_u16()),
+    }
+}
+
+#[tokio::test]
+async fn login_info_without_clone() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/private", get(get_private))
+            .route_layer(
+                AuthLayer::new(auth_handler())
+                    .with_verification_cache(16, std::time::Duration::from_secs(60)),
+            ),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("user");
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn login_info_with_verification_error() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/hybrid", get(get_hybrid))
+            .route_layer(AuthLayer::new(auth_handler())),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/hybrid").await;
+    response.assert_status_ok();
+    response.assert_text("anonymous");
+
+    let response = server
+        .get("/hybrid")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("user");
+
+    let response = server
+        .get("/hybrid")
+        .add_header("cookie", "access_token=junk")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("token rejected: 401");
+}
@@ -0,0 +1,93 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor, RenewalDecision},
+};
+
+const BROWSER_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes() -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/forbidden", get(get_forbidden))
+        .route_layer(AuthLayer::new(auth_handler()).with_login_redirect("/login"))
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn get_forbidden() -> StatusCode {
+    StatusCode::FORBIDDEN
+}
+
+#[tokio::test]
+async fn redirect_browser_to_login() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", BROWSER_ACCEPT)
+        .await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    response.assert_header("location", "/login");
+}
+
+#[tokio::test]
+async fn keep_unauthorized_for_api_clients() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", "application/json")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn no_redirect_for_other_responses() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", BROWSER_ACCEPT)
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("private");
+
+    let response = server
+        .get("/forbidden")
+        .add_header("accept", BROWSER_ACCEPT)
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_forbidden();
+}
@@ -1,5 +1,69 @@
+mod access_token_expire_response;
+mod access_token_expiry_required;
+mod access_token_grace_period;
+mod access_token_query_parameter;
+mod app_builder;
 mod app_state;
+mod auth_audit;
+mod auth_config;
+mod auth_context;
+mod auth_realms;
+mod auth_validation;
 mod authentication_with_refresh_token;
 mod authentication_without_refresh_token;
 mod authorization;
+mod base_path;
+mod bearer_token;
+mod body_limit;
+mod clear_site_data;
+mod compression;
+mod connect_info;
+mod cookie_attributes;
+mod cookie_debug_header;
+mod cookie_expiration;
+mod csp_nonce;
+mod drain_503;
+mod fallback;
+mod fn_auth_handler;
+#[cfg(feature = "form-csrf")]
+mod form_csrf;
+mod graceful_shutdown;
+mod handler_panic;
+mod health_route;
+mod helper_stack;
+mod https_redirect;
+mod inflight_gauge;
+#[cfg(feature = "jwt")]
+mod jwt_auth_handler;
+mod login_info_extractor;
+mod login_redirect;
+mod negotiated_error_responses;
+mod normalize_path;
+mod parallel_verification;
+mod poll_ready;
+mod public_paths;
+mod public_route;
+mod rate_limit;
+mod recently_authenticated;
+mod reject_invalid_access_token;
+mod renewal_decision;
+mod renewal_threshold;
+mod require_content_type;
+mod require_fresh_access_token;
 mod response_http_header_mutator;
+mod response_timing;
+mod reuse_port;
+mod safe_redirect;
+mod security_headers;
+mod session_control;
+mod support;
+mod tenant_extractor;
+mod token_acceptance;
+mod token_binding;
+mod token_conversion;
+mod token_expiry;
+mod token_generator;
+mod token_sources;
+mod tracing_user;
+mod vary_cookie;
+mod verification_cache;
@@ -0,0 +1,170 @@
+use axum::{extract::Path, http::StatusCode, routing::get, Router};
+use serde_json::json;
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthError, AuthLayer, FnAuthHandler, LoginInfoExtractor, RenewalDecision},
+};
+
+const BROWSER_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+
+const AUTH_ERRORS: [AuthError; 4] = [
+    AuthError::MissingToken,
+    AuthError::InvalidAccessToken,
+    AuthError::InvalidRefreshToken,
+    AuthError::Forbidden,
+];
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes(negotiated_error_responses: bool) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/forbidden", get(get_forbidden))
+        .route("/custom-unauthorized", get(get_custom_unauthorized))
+        .route("/auth-error/:index", get(get_auth_error))
+        .route_layer(
+            AuthLayer::new(auth_handler())
+                .with_negotiated_error_responses(negotiated_error_responses),
+        )
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn get_forbidden() -> AuthError {
+    AuthError::Forbidden
+}
+
+async fn get_auth_error(Path(index): Path<usize>) -> AuthError {
+    AUTH_ERRORS[index]
+}
+
+async fn get_custom_unauthorized() -> (StatusCode, &'static str) {
+    (StatusCode::UNAUTHORIZED, "custom")
+}
+
+#[tokio::test]
+async fn json_error_response() {
+    let app = AxumApp::new(routes(true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", "application/json")
+        .await;
+    response.assert_status_unauthorized();
+    response.assert_header("content-type", "application/json");
+    response.assert_text(r#"{"error":"unauthorized"}"#);
+
+    let response = server.get("/forbidden").await;
+    response.assert_status_forbidden();
+    response.assert_header("content-type", "application/json");
+    response.assert_text(r#"{"error":"forbidden"}"#);
+
+    let response = server
+        .get("/private")
+        .add_header("accept", "text/html;q=0.5, application/json")
+        .await;
+    response.assert_text(r#"{"error":"unauthorized"}"#);
+}
+
+#[tokio::test]
+async fn json_error_body_schema() {
+    let app = AxumApp::new(routes(true));
+    let server = app.spawn_test_server().unwrap();
+
+    let expected_error_bodies = [
+        (StatusCode::UNAUTHORIZED, json!({ "error": "unauthorized" })),
+        (StatusCode::BAD_REQUEST, json!({ "error": "bad_request" })),
+        (StatusCode::BAD_REQUEST, json!({ "error": "bad_request" })),
+        (StatusCode::FORBIDDEN, json!({ "error": "forbidden" })),
+    ];
+    for (index, (status_code, error_body)) in expected_error_bodies.iter().enumerate() {
+        let response = server
+            .get(&format!("/auth-error/{index}"))
+            .add_header("accept", "application/json")
+            .await;
+        response.assert_status(*status_code);
+        response.assert_json(error_body);
+    }
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+    response.assert_json(&json!({ "error": "unauthorized" }));
+}
+
+#[tokio::test]
+async fn html_error_response() {
+    let app = AxumApp::new(routes(true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", BROWSER_ACCEPT)
+        .await;
+    response.assert_status_unauthorized();
+    response.assert_header("content-type", "text/html; charset=utf-8");
+    assert!(response.text().contains("<h1>401 Unauthorized</h1>"));
+
+    let response = server
+        .get("/forbidden")
+        .add_header("accept", "application/json;q=0.5, text/html")
+        .await;
+    response.assert_status_forbidden();
+    assert!(response.text().contains("<h1>403 Forbidden</h1>"));
+}
+
+#[tokio::test]
+async fn error_response_with_body_untouched() {
+    let app = AxumApp::new(routes(true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/custom-unauthorized")
+        .add_header("accept", BROWSER_ACCEPT)
+        .await;
+    response.assert_status_unauthorized();
+    response.assert_text("custom");
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid")
+        .add_header("accept", BROWSER_ACCEPT)
+        .await;
+    response.assert_status_ok();
+    response.assert_text("private");
+}
+
+#[tokio::test]
+async fn negotiated_error_responses_disabled_by_default() {
+    let app = AxumApp::new(routes(false));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("accept", "application/json")
+        .await;
+    response.assert_status_unauthorized();
+    response.assert_text("");
+}
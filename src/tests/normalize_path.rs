@@ -0,0 +1,89 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{StatusCode, Uri},
+    response::Response,
+    routing::get,
+    Router,
+};
+use http_body_util::BodyExt;
+use tower::{Layer, Service};
+
+use crate::normalize_path::{NormalizePathLayer, TrailingSlash};
+
+async fn get_uri(uri: Uri) -> String {
+    uri.to_string()
+}
+
+fn routes(trailing_slash: TrailingSlash) -> impl Service<Request, Response = Response> + Clone {
+    let account_path = match trailing_slash {
+        TrailingSlash::Trim => "/account",
+        TrailingSlash::Append => "/account/",
+    };
+    let router = Router::new()
+        .route("/", get(get_uri))
+        .route(account_path, get(get_uri));
+
+    NormalizePathLayer::new(trailing_slash).layer(router)
+}
+
+async fn send_get(
+    service: &mut (impl Service<Request, Response = Response> + Clone),
+    uri: &str,
+) -> (StatusCode, String) {
+    std::future::poll_fn(|cx| service.poll_ready(cx))
+        .await
+        .unwrap_or_else(|_| panic!("the service should be ready"));
+    let response = service
+        .call(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap_or_else(|_| panic!("the service should respond"));
+
+    let status_code = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status_code, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn trim_trailing_slash() {
+    let mut service = routes(TrailingSlash::Trim);
+
+    assert_eq!(
+        send_get(&mut service, "/account").await,
+        (StatusCode::OK, "/account".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/account/").await,
+        (StatusCode::OK, "/account".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/account//?tab=1").await,
+        (StatusCode::OK, "/account?tab=1".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/").await,
+        (StatusCode::OK, "/".to_string())
+    );
+}
+
+#[tokio::test]
+async fn append_trailing_slash() {
+    let mut service = routes(TrailingSlash::Append);
+
+    assert_eq!(
+        send_get(&mut service, "/account").await,
+        (StatusCode::OK, "/account/".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/account/").await,
+        (StatusCode::OK, "/account/".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/account?tab=1").await,
+        (StatusCode::OK, "/account/?tab=1".to_string())
+    );
+    assert_eq!(
+        send_get(&mut service, "/").await,
+        (StatusCode::OK, "/".to_string())
+    );
+}
@@ -0,0 +1,117 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor, RenewalDecision},
+};
+
+/// Counts the verifications of `auth_handler` and the verifications running at the same time.
+#[derive(Clone, Default)]
+struct AppState {
+    verifications: Arc<AtomicUsize>,
+    running: Arc<AtomicUsize>,
+    max_running: Arc<AtomicUsize>,
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+/// Accepts the `valid` access token after a delay, like a verification over the network.
+fn auth_handler(state: AppState) -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        move |access_token| {
+            let state = state.clone();
+            Box::pin(async move {
+                state.verifications.fetch_add(1, Ordering::SeqCst);
+                let running = state.running.fetch_add(1, Ordering::SeqCst) + 1;
+                state.max_running.fetch_max(running, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                state.running.fetch_sub(1, Ordering::SeqCst);
+
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes(state: AppState, parallel_access_token_verification: bool) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(
+            AuthLayer::new(auth_handler(state))
+                .with_parallel_access_token_verification(parallel_access_token_verification),
+        )
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn accept_valid_one_of_duplicate_cookies() {
+    let state = AppState::default();
+    let app = AxumApp::new(routes(state.clone(), true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header(
+            "cookie",
+            "access_token=stale-1; access_token=valid; access_token=stale-2",
+        )
+        .await;
+    response.assert_status_ok();
+    assert_eq!(state.verifications.load(Ordering::SeqCst), 3);
+    assert_eq!(state.max_running.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn verify_at_most_4_cookies() {
+    let state = AppState::default();
+    let app = AxumApp::new(routes(state.clone(), true));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header(
+            "cookie",
+            (1..=5)
+                .map(|index| format!("access_token=junk-{index}"))
+                .chain(["access_token=valid".to_string()])
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(state.verifications.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn verify_single_cookie_by_default() {
+    let state = AppState::default();
+    let app = AxumApp::new(routes(state.clone(), false));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid; access_token=stale")
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(state.verifications.load(Ordering::SeqCst), 1);
+}
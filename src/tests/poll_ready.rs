@@ -0,0 +1,198 @@
+use std::{
+    convert::Infallible,
+    future::{poll_fn, Ready},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::{
+    auth::{AuthLayer, AuthMiddleware, FnAuthHandler},
+    body_limit::BodyLimitLayer,
+    csp_nonce::CspNonceLayer,
+    https_redirect::HttpsRedirectLayer,
+    require_content_type::RequireContentTypeLayer,
+    response_http_header_mutator::ResponseHttpHeaderMutatorLayer,
+    response_timing::ResponseTimingLayer,
+    tests::support::stub_auth_handler,
+};
+
+/// Inner service that panics when it is called without being polled ready first.
+struct ReadinessCheckingService {
+    ready: bool,
+}
+
+impl Clone for ReadinessCheckingService {
+    fn clone(&self) -> Self {
+        Self { ready: false }
+    }
+}
+
+impl<RequestBodyType> Service<Request<RequestBodyType>> for ReadinessCheckingService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Ready<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.ready = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _req: Request<RequestBodyType>) -> Self::Future {
+        assert!(self.ready, "called without poll_ready");
+        self.ready = false;
+        std::future::ready(Ok("ok".into_response()))
+    }
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+#[tokio::test]
+async fn auth_middleware_calls_the_ready_inner_service() {
+    let mut service =
+        AuthLayer::new(auth_handler()).layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let request = Request::get("/")
+            .header("cookie", "access_token=access-token")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn response_http_header_mutator_middleware_calls_the_ready_inner_service() {
+    let mut service =
+        ResponseHttpHeaderMutatorLayer::new(|_req_headers, _res_headers| Ok::<(), Infallible>(()))
+            .layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        // the middleware accepts any request body type, so it has to be named
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let response = service
+            .call(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn csp_nonce_middleware_calls_the_ready_inner_service() {
+    let mut service =
+        CspNonceLayer::new("script-src 'self'").layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let response = service
+            .call(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn https_redirect_middleware_calls_the_ready_inner_service() {
+    let mut service = HttpsRedirectLayer::new().layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let request = Request::get("/")
+            .header("x-forwarded-proto", "https")
+            .body(Body::empty())
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn body_limit_middleware_calls_the_ready_inner_service() {
+    let mut service = BodyLimitLayer::new(1024).layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let response = service
+            .call(Request::post("/").body(Body::from("body")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn require_content_type_middleware_calls_the_ready_inner_service() {
+    let mut service = RequireContentTypeLayer::new("application/json")
+        .layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let request = Request::post("/")
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn response_timing_middleware_calls_the_ready_inner_service() {
+    let mut service = ResponseTimingLayer::new(HeaderName::from_static("x-response-time"))
+        .layer(ReadinessCheckingService { ready: false });
+
+    for _ in 0..2 {
+        poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+            .await
+            .unwrap();
+        let response = service
+            .call(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[tokio::test]
+async fn auth_middleware_constructed_without_layer() {
+    let mut service =
+        AuthMiddleware::new(ReadinessCheckingService { ready: false }, auth_handler());
+
+    poll_fn(|cx| Service::<Request>::poll_ready(&mut service, cx))
+        .await
+        .unwrap();
+    let request = Request::get("/")
+        .header("cookie", "access_token=access-token")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,63 @@
+use axum::{routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor},
+    tests::support::renewing_stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    renewing_stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+fn is_public_path(path: &str) -> bool {
+    let segments = path.split('/').collect::<Vec<_>>();
+    matches!(segments.as_slice(), ["", "users", _, "avatar"])
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/users/:id/avatar", get(get_hybrid))
+        .route("/users/:id/settings", get(get_hybrid))
+        .route_layer(AuthLayer::new(state.clone()).with_public_path_matcher(is_public_path))
+        .with_state(state)
+}
+
+async fn get_hybrid(login_info: Option<LoginInfoExtractor<LoginInfo>>) -> &'static str {
+    if login_info.is_some() {
+        "authenticated"
+    } else {
+        "unauthenticated"
+    }
+}
+
+#[tokio::test]
+async fn public_path_skips_authentication() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/users/42/avatar")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("unauthenticated");
+    assert!(response.maybe_cookie("access_token").is_none());
+}
+
+#[tokio::test]
+async fn non_public_path_requires_authentication() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/users/42/settings")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("authenticated");
+    assert!(response.maybe_cookie("access_token").is_some());
+}
@@ -0,0 +1,63 @@
+use axum::{routing::get, Extension, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor, PublicRoute},
+    tests::support::renewing_stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    renewing_stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    let auth_layer = AuthLayer::new(state.clone());
+    Router::new()
+        .route(
+            "/avatar",
+            get(get_hybrid)
+                .route_layer(auth_layer.clone())
+                .layer(Extension(PublicRoute)),
+        )
+        .route("/settings", get(get_hybrid).route_layer(auth_layer))
+        .with_state(state)
+}
+
+async fn get_hybrid(login_info: Option<LoginInfoExtractor<LoginInfo>>) -> &'static str {
+    if login_info.is_some() {
+        "authenticated"
+    } else {
+        "unauthenticated"
+    }
+}
+
+#[tokio::test]
+async fn public_route_skips_authentication() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/avatar")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("unauthenticated");
+    assert!(response.maybe_cookie("access_token").is_none());
+}
+
+#[tokio::test]
+async fn unmarked_route_requires_authentication() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/settings")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("authenticated");
+    assert!(response.maybe_cookie("access_token").is_some());
+}
@@ -0,0 +1,149 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use axum::{
+    extract::ConnectInfo,
+    http::{header::RETRY_AFTER, StatusCode},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{login_info_from_parts, AuthLayer, FnAuthHandler},
+    rate_limit::RateLimitLayer,
+    tests::support::stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+}
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|access_token| {
+        access_token
+            .strip_prefix("token-of-")
+            .map(|loginname| LoginInfo {
+                loginname: loginname.to_string(),
+            })
+            .ok_or(StatusCode::UNAUTHORIZED)
+    })
+}
+
+#[derive(Hash, Eq, PartialEq)]
+enum RateLimitKey {
+    User(String),
+    Peer(Option<IpAddr>),
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route_layer(RateLimitLayer::new(2, Duration::from_secs(60), |parts| {
+            match login_info_from_parts::<LoginInfo>(parts) {
+                Some(login_info) => RateLimitKey::User(login_info.loginname.clone()),
+                None => RateLimitKey::Peer(
+                    parts
+                        .extensions
+                        .get::<ConnectInfo<SocketAddr>>()
+                        .map(|ConnectInfo(peer_address)| peer_address.ip()),
+                ),
+            }
+        }))
+        .route_layer(AuthLayer::new(state.clone()))
+        .with_state(state)
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[tokio::test]
+async fn rate_limit_by_login_info() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    for _ in 0..2 {
+        let response = server
+            .get("/")
+            .add_header("cookie", "access_token=token-of-alice")
+            .await;
+        response.assert_status_ok();
+    }
+
+    let response = server
+        .get("/")
+        .add_header("cookie", "access_token=token-of-alice")
+        .await;
+    response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response
+        .header(RETRY_AFTER)
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=30).contains(&retry_after));
+
+    let response = server
+        .get("/")
+        .add_header("cookie", "access_token=token-of-bob")
+        .await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn rate_limit_unauthenticated_by_peer_address() {
+    let app = AxumApp::new(routes(auth_handler())).with_connect_info();
+    let server = app.spawn_test_server().unwrap();
+
+    for _ in 0..2 {
+        server.get("/").await.assert_status_ok();
+    }
+    server
+        .get("/")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    let response = server
+        .get("/")
+        .add_header("cookie", "access_token=token-of-alice")
+        .await;
+    response.assert_status_ok();
+}
+
+fn degenerate_routes(max_requests: u32, period: Duration) -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route_layer(RateLimitLayer::new(max_requests, period, |_parts| ()))
+}
+
+#[tokio::test]
+async fn reject_every_request_without_max_requests() {
+    let app = AxumApp::new(degenerate_routes(0, Duration::from_secs(60)));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.header(RETRY_AFTER), u64::MAX.to_string());
+
+    let app = AxumApp::new(degenerate_routes(0, Duration::ZERO));
+    let server = app.spawn_test_server().unwrap();
+
+    server
+        .get("/")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn accept_every_request_without_period() {
+    let app = AxumApp::new(degenerate_routes(1, Duration::ZERO));
+    let server = app.spawn_test_server().unwrap();
+
+    for _ in 0..4 {
+        server.get("/").await.assert_status_ok();
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthHandler, AuthLayer, RecentlyAuthenticated, RefreshToken, RenewalDecision,
+    },
+};
+
+/// Accepts `fresh`, `stale` and `unknown` access tokens, which were authenticated a minute ago,
+/// an hour ago and at an unknown time.
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        match access_token.as_str() {
+            "fresh" | "stale" | "unknown" => Ok(LoginInfo),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+
+    async fn authenticated_at(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> Option<OffsetDateTime> {
+        match access_token.as_str() {
+            "fresh" => Some(OffsetDateTime::now_utc() - Duration::minutes(1)),
+            "stale" => Some(OffsetDateTime::now_utc() - Duration::hours(1)),
+            _ => None,
+        }
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+/// Requires an authentication within the last 5 minutes.
+async fn delete_account(_recently_authenticated: RecentlyAuthenticated<300>) -> &'static str {
+    "deleted"
+}
+
+#[tokio::test]
+async fn recently_authenticated() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/delete-account", get(delete_account))
+            .route_layer(AuthLayer::new(AppState)),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/delete-account")
+        .add_header("cookie", "access_token=fresh")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("deleted");
+
+    for access_token in ["stale", "unknown", "invalid"] {
+        let response = server
+            .get("/delete-account")
+            .add_header("cookie", format!("access_token={access_token}"))
+            .await;
+        response.assert_status_unauthorized();
+    }
+
+    let response = server.get("/delete-account").await;
+    response.assert_status_unauthorized();
+}
@@ -0,0 +1,73 @@
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginStatus},
+    tests::support::stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|access_token| {
+        if access_token.as_str() == "valid-access-token" {
+            Ok(LoginInfo)
+        } else {
+            Err(StatusCode::BAD_REQUEST)
+        }
+    })
+}
+
+fn routes(auth_layer: AuthLayer<LoginInfo, FnAuthHandler<LoginInfo>>) -> Router {
+    Router::new()
+        .route("/hybrid", get(get_hybrid))
+        .route_layer(auth_layer)
+}
+
+async fn get_hybrid(login_status: LoginStatus<LoginInfo>) -> &'static str {
+    match login_status {
+        LoginStatus::Anonymous => "anonymous",
+        LoginStatus::Invalid(_status_code) => "invalid",
+        LoginStatus::Authenticated(_login_info) => "authenticated",
+    }
+}
+
+#[tokio::test]
+async fn invalid_access_token_falls_through_by_default() {
+    let app = AxumApp::new(routes(AuthLayer::new(auth_handler())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/hybrid")
+        .add_header("cookie", "access_token=invalid-access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("invalid");
+}
+
+#[tokio::test]
+async fn reject_invalid_access_token() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(auth_handler()).with_reject_invalid_access_token(true),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/hybrid")
+        .add_header("cookie", "access_token=invalid-access-token")
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    assert_eq!(response.cookie("access_token").value(), "");
+
+    let response = server.get("/hybrid").await;
+    response.assert_status_ok();
+    response.assert_text("anonymous");
+
+    let response = server
+        .get("/hybrid")
+        .add_header("cookie", "access_token=valid-access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("authenticated");
+}
@@ -0,0 +1,293 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderName, StatusCode},
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
+        LoginInfoExtractor, RefreshToken, RenewalDecision, RenewedThisRequest,
+    },
+};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct AppState {
+    logins: Arc<Mutex<BTreeMap<AccessToken, LoginInfo>>>,
+}
+
+impl AppState {
+    fn new() -> Self {
+        Self {
+            logins: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        self.logins
+            .lock()
+            .get(access_token)
+            .cloned()
+            .ok_or(StatusCode::BAD_REQUEST)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        match login_info.loginname.as_str() {
+            "keep" => RenewalDecision::Keep,
+            "expire" => RenewalDecision::Expire,
+            "fail" => RenewalDecision::Failed,
+            "session" => RenewalDecision::RenewSession(access_token.clone()),
+            "rotate" => {
+                let renewed_access_token =
+                    AccessToken::new(Uuid::new_v4().as_hyphenated().to_string());
+                self.logins
+                    .lock()
+                    .insert(renewed_access_token.clone(), login_info.as_ref().clone());
+                RenewalDecision::Renew(renewed_access_token, ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+            }
+            _ => {
+                RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+            }
+        }
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+        self.logins.lock().remove(access_token);
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        unreachable!("tests contained in this file, this line should not be called")
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {
+        unreachable!("tests contained in this file, this line should not be called")
+    }
+}
+
+fn routes(state: AppState) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/renewed", get(get_renewed))
+        .route("/api/login/:loginname", post(api_login))
+        .route("/api/logout", post(api_logout))
+        .route_layer(
+            AuthLayer::new(state.clone())
+                .with_renewal_failure_header(HeaderName::from_static("x-session-renewal")),
+        )
+        .with_state(state)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+async fn get_renewed(RenewedThisRequest(renewed): RenewedThisRequest) -> String {
+    renewed.to_string()
+}
+
+async fn api_logout() -> AuthLogoutResponse {
+    AuthLogoutResponse::new(None::<String>, None::<String>)
+}
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+}
+
+async fn api_login(
+    State(state): State<AppState>,
+    Path(loginname): Path<String>,
+) -> AccessTokenResponse {
+    let access_token = AccessToken::new(Uuid::new_v4().as_hyphenated().to_string());
+    let access_token_response = if loginname == "session" {
+        AccessTokenResponse::session(access_token, None)
+    } else {
+        AccessTokenResponse::with_time_delta(
+            access_token,
+            ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+            None,
+        )
+    };
+
+    state.logins.lock().insert(
+        access_token_response.token().clone(),
+        LoginInfo { loginname },
+    );
+
+    access_token_response
+}
+
+#[tokio::test]
+async fn renew_access_token() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/renew").await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    assert!(response.maybe_cookie("access_token").is_some());
+}
+
+#[tokio::test]
+async fn keep_access_token() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/keep").await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    assert!(response.maybe_cookie("access_token").is_none());
+    assert!(!response.headers().contains_key("x-session-renewal"));
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn fail_access_token_renewal() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/fail").await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    response.assert_header("x-session-renewal", "failed");
+    assert!(response.maybe_cookie("access_token").is_none());
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+}
+
+#[tokio::test]
+async fn expire_access_token() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/expire").await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("access_token").value(), "");
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn renewed_this_request() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server.get("/renewed").await;
+    response.assert_text("false");
+
+    server.post("/api/login/renew").await;
+
+    let response = server.get("/renewed").await;
+    response.assert_text("true");
+}
+
+#[tokio::test]
+async fn not_renewed_this_request() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/keep").await;
+
+    let response = server.get("/renewed").await;
+    response.assert_text("false");
+}
+
+#[tokio::test]
+async fn renew_session_access_token() {
+    let app = AxumApp::new(routes(AppState::new()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server.post("/api/login/session").await;
+    let access_token_cookie = response.cookie("access_token");
+    assert_eq!(access_token_cookie.expires(), None);
+    assert_eq!(access_token_cookie.max_age(), None);
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    let access_token_cookie = response.cookie("access_token");
+    assert!(!access_token_cookie.value().is_empty());
+    assert_eq!(access_token_cookie.expires(), None);
+    assert_eq!(access_token_cookie.max_age(), None);
+
+    let response = server.get("/renewed").await;
+    response.assert_text("true");
+}
+
+#[tokio::test]
+async fn revoke_renewed_access_token_discarded_by_logout() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login/rotate").await;
+    assert_eq!(state.logins.lock().len(), 1);
+
+    let response = server.post("/api/logout").await;
+    response.assert_status_ok();
+    assert!(state.logins.lock().is_empty());
+}
+
+#[tokio::test]
+async fn revoke_renewed_access_token_discarded_by_access_token_response() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone()));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let response = server.post("/api/login/rotate").await;
+    let access_token = AccessToken::new(response.cookie("access_token").value().to_string());
+
+    let response = server.post("/api/login/renew").await;
+    let relogin_access_token =
+        AccessToken::new(response.cookie("access_token").value().to_string());
+
+    let logins = state.logins.lock();
+    assert_eq!(logins.len(), 2);
+    assert!(logins.contains_key(&access_token));
+    assert!(logins.contains_key(&relogin_access_token));
+}
@@ -0,0 +1,104 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+use time::OffsetDateTime;
+
+use crate::{
+    app::AxumApp,
+    auth::{AccessToken, AuthHandler, AuthLayer, RefreshToken, RenewalDecision},
+};
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+struct AppState;
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        Ok(LoginInfo)
+    }
+
+    async fn access_token_lifetime(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> Option<Range<OffsetDateTime>> {
+        let now = OffsetDateTime::now_utc();
+        let issued_at = match access_token.as_str() {
+            "fresh" => now,
+            "old" => now - Duration::from_secs(50 * 60),
+            _ => return None,
+        };
+        Some(issued_at..issued_at + ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn routes(auth_layer: AuthLayer<LoginInfo, AppState>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(auth_layer)
+}
+
+async fn get_private() -> &'static str {
+    "private"
+}
+
+async fn is_renewed(server: &axum_test::TestServer, access_token: &str) -> bool {
+    let response = server
+        .get("/private")
+        .add_header("cookie", format!("access_token={access_token}"))
+        .await;
+    response.assert_status_ok();
+    response.maybe_cookie("access_token").is_some()
+}
+
+#[tokio::test]
+async fn renew_below_threshold() {
+    let app = AxumApp::new(routes(AuthLayer::new(AppState).with_renewal_threshold(0.5)));
+    let server = app.spawn_test_server().unwrap();
+
+    assert!(!is_renewed(&server, "fresh").await);
+    assert!(is_renewed(&server, "old").await);
+    assert!(is_renewed(&server, "without-lifetime").await);
+}
+
+#[tokio::test]
+async fn renew_on_every_request_by_default() {
+    let app = AxumApp::new(routes(AuthLayer::new(AppState)));
+    let server = app.spawn_test_server().unwrap();
+
+    assert!(is_renewed(&server, "fresh").await);
+    assert!(is_renewed(&server, "old").await);
+}
@@ -0,0 +1,77 @@
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{app::AxumApp, require_content_type::RequireContentTypeLayer};
+
+fn routes() -> Router {
+    Router::new()
+        .route("/api/items", get(get_items))
+        .route("/api/items", post(post_item))
+        .route_layer(RequireContentTypeLayer::new("application/json"))
+}
+
+async fn get_items() -> &'static str {
+    "items"
+}
+
+async fn post_item() -> &'static str {
+    "created"
+}
+
+#[tokio::test]
+async fn get_without_content_type() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/api/items").await;
+    response.assert_status_ok();
+    response.assert_text("items");
+}
+
+#[tokio::test]
+async fn post_with_json_content_type() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/items")
+        .text("{}")
+        .content_type("application/json; charset=utf-8")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("created");
+}
+
+#[tokio::test]
+async fn post_with_other_content_type() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/items").text("text").await;
+    response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn post_without_content_type() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/api/items").await;
+    response.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn post_with_empty_body_without_content_type() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/items")
+        .add_header("content-length", "0")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("created");
+}
@@ -0,0 +1,92 @@
+use axum::{http::StatusCode, routing::post, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, RefreshTokenExtractor, RequireFreshAccessToken},
+    tests::support::stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|access_token| {
+        if access_token.as_str() == "valid-access-token" {
+            Ok(LoginInfo)
+        } else {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    })
+    .with_refresh_token_fns(
+        |refresh_token| {
+            Box::pin(async move {
+                if refresh_token.as_str() == "valid-refresh-token" {
+                    Ok(())
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_refresh_token| Box::pin(async {}),
+    )
+}
+
+fn routes(state: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/change-password", post(api_change_password))
+        .route("/api/refresh-login", post(api_refresh_login))
+        .route_layer(AuthLayer::new(state.clone()))
+        .with_state(state)
+}
+
+async fn api_change_password(
+    RequireFreshAccessToken(_login_info): RequireFreshAccessToken<LoginInfo>,
+) -> &'static str {
+    "password changed"
+}
+
+async fn api_refresh_login(
+    RefreshTokenExtractor(_refresh_token): RefreshTokenExtractor,
+) -> &'static str {
+    "refreshed"
+}
+
+#[tokio::test]
+async fn reject_refresh_token_without_access_token() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/refresh-login")
+        .add_header("cookie", "refresh_token=valid-refresh-token")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .post("/api/change-password")
+        .add_header("cookie", "refresh_token=valid-refresh-token")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .post("/api/change-password")
+        .add_header(
+            "cookie",
+            "refresh_token=valid-refresh-token; access_token=invalid-access-token",
+        )
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn accept_valid_access_token() {
+    let app = AxumApp::new(routes(auth_handler()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/change-password")
+        .add_header("cookie", "access_token=valid-access-token")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("password changed");
+}
@@ -1,8 +1,26 @@
-use std::convert::Infallible;
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
 
-use axum::{routing::get, Router};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header::CACHE_CONTROL, HeaderValue, Method, StatusCode},
+    routing::{get, post},
+    Router,
+};
+use http_body::Frame;
+use http_body_util::BodyExt;
+use tokio::sync::{mpsc, Mutex};
+use tower::Service;
 
-use crate::{app::AxumApp, response_http_header_mutator::ResponseHttpHeaderMutatorLayer};
+use crate::{
+    app::AxumApp, body_limit::BodyLimitLayer,
+    response_http_header_mutator::ResponseHttpHeaderMutatorLayer,
+};
 
 #[derive(Clone)]
 struct AppState;
@@ -41,3 +59,201 @@ async fn copy_header_from_request() {
     assert_eq!(response.headers().get("header-name-1").unwrap(), "value-1");
     assert_eq!(response.headers().get("header-name-2").unwrap(), "value-2");
 }
+
+/// Body streaming the chunks received on the channel, it ends when the sender is dropped.
+struct ChannelBody(mpsc::Receiver<Bytes>);
+
+impl http_body::Body for ChannelBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        self.0
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|chunk| Ok(Frame::data(chunk))))
+    }
+}
+
+type ChunkReceiver = std::sync::Arc<Mutex<Option<mpsc::Receiver<Bytes>>>>;
+
+async fn get_events(State(chunk_receiver): State<ChunkReceiver>) -> Body {
+    Body::new(ChannelBody(chunk_receiver.lock().await.take().unwrap()))
+}
+
+#[tokio::test]
+async fn stream_body_untouched() {
+    let (chunk_sender, chunk_receiver) = mpsc::channel(1);
+    let mut router = Router::new()
+        .route("/events", get(get_events))
+        .route_layer(ResponseHttpHeaderMutatorLayer::new(
+            |_req_headers, res_headers| {
+                res_headers.insert("header-name", HeaderValue::from_static("value"));
+                Ok::<(), Infallible>(())
+            },
+        ))
+        .with_state(std::sync::Arc::new(Mutex::new(Some(chunk_receiver))));
+
+    let response = router
+        .call(Request::get("/events").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.headers().get("header-name").unwrap(), "value");
+
+    let mut body = response.into_body();
+    for chunk in ["data: 0\n\n", "data: 1\n\n"] {
+        chunk_sender.send(Bytes::from(chunk)).await.unwrap();
+
+        // the chunk arrives while the stream is still open
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await
+            .expect("the chunk should arrive without waiting for the end of the stream")
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.into_data().unwrap(), chunk);
+    }
+
+    drop(chunk_sender);
+    assert!(body.frame().await.is_none());
+}
+
+/// Request body sending a chunk, then failing like a client disconnecting mid-upload.
+struct FailingBody(bool);
+
+impl http_body::Body for FailingBody {
+    type Data = Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.0 {
+            Poll::Ready(Some(Err(std::io::ErrorKind::ConnectionReset.into())))
+        } else {
+            self.0 = true;
+            Poll::Ready(Some(Ok(Frame::data(Bytes::from("partial")))))
+        }
+    }
+}
+
+async fn post_upload(body: Body) -> StatusCode {
+    match body.collect().await {
+        Ok(_) => StatusCode::OK,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Responds with success even if the request body is incomplete.
+async fn post_upload_ignoring_errors(body: Body) -> StatusCode {
+    let _ = body.collect().await;
+    StatusCode::OK
+}
+
+async fn post_bytes(_body: Bytes) -> StatusCode {
+    StatusCode::OK
+}
+
+fn upload_routes() -> Router {
+    Router::new()
+        .route("/upload", post(post_upload))
+        .route("/upload-ignoring-errors", post(post_upload_ignoring_errors))
+        .route("/bytes", post(post_bytes))
+        .route_layer(ResponseHttpHeaderMutatorLayer::new(
+            |_req_headers, res_headers| {
+                res_headers.insert("header-name", HeaderValue::from_static("value"));
+                Ok::<(), Infallible>(())
+            },
+        ))
+}
+
+#[tokio::test]
+async fn request_body_error() {
+    let response = upload_routes()
+        .call(
+            Request::post("/upload-ignoring-errors")
+                .body(Body::new(FailingBody(false)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(!response.headers().contains_key("header-name"));
+
+    // the error response of the inner service is kept
+    let response = upload_routes()
+        .call(
+            Request::post("/upload")
+                .body(Body::new(FailingBody(false)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!response.headers().contains_key("header-name"));
+
+    let response = upload_routes()
+        .call(
+            Request::post("/upload")
+                .body(Body::from("complete"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("header-name").unwrap(), "value");
+}
+
+#[tokio::test]
+async fn request_body_over_body_limit() {
+    let mut routes = upload_routes().route_layer(BodyLimitLayer::new(8));
+
+    // without a Content-Length the limit is hit while the body is read
+    let response = routes
+        .call(
+            Request::post("/bytes")
+                .body(Body::from_stream(futures_util::stream::iter([Ok::<
+                    _,
+                    Infallible,
+                >(
+                    Bytes::from("0123456789"),
+                )])))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(response.headers().get("header-name").unwrap(), "value");
+
+    let response = routes
+        .call(Request::post("/bytes").body(Body::from("01234")).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn headers_by_request_method() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/", get(get_index).post(get_index))
+            .route_layer(ResponseHttpHeaderMutatorLayer::new_with_method(
+                |method: &Method, _req_headers: &_, res_headers: &mut axum::http::HeaderMap| {
+                    if method == Method::GET {
+                        res_headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+                    }
+                    Ok::<(), Infallible>(())
+                },
+            )),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_header(CACHE_CONTROL, "max-age=60");
+
+    let response = server.post("/").await;
+    response.assert_status_ok();
+    assert!(!response.headers().contains_key(CACHE_CONTROL));
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use axum::{http::HeaderName, routing::get, Router};
+
+use crate::{app::AxumApp, response_timing::ResponseTimingLayer};
+
+fn routes() -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route_layer(ResponseTimingLayer::new(HeaderName::from_static(
+            "x-response-time-ms",
+        )))
+}
+
+async fn get_index() -> &'static str {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    "index"
+}
+
+#[tokio::test]
+async fn response_time_header() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+
+    let elapsed_ms = response
+        .header("x-response-time-ms")
+        .to_str()
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+    assert!(elapsed_ms >= 10);
+}
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use axum::{routing::get, Router};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::app::{AxumApp, RunServerError};
+
+fn routes() -> Router {
+    Router::new().route("/", get(get_index))
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn two_apps_share_port() {
+    let mut app_1 = AxumApp::new(routes()).with_reuse_port(true);
+    let (local_address, _joinhandle) = app_1
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut app_2 = AxumApp::new(routes()).with_reuse_port(true);
+    app_2
+        .spawn_server_handle(local_address)
+        .await
+        .expect("second app should bind the same port");
+
+    let mut stream = TcpStream::connect(local_address).await.unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.ends_with("index"));
+
+    for app in [&mut app_1, &mut app_2] {
+        app.stop_server();
+        tokio::time::timeout(Duration::from_secs(5), app.join())
+            .await
+            .expect("server should stop");
+    }
+}
+
+#[tokio::test]
+async fn port_not_shared_by_default() {
+    let mut app_1 = AxumApp::new(routes());
+    let (local_address, _joinhandle) = app_1
+        .spawn_server_handle("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+
+    let mut app_2 = AxumApp::new(routes());
+    let result = app_2.spawn_server_handle(local_address).await;
+    assert!(matches!(result, Err(RunServerError::TcpBind(_))));
+
+    app_1.stop_server();
+    tokio::time::timeout(Duration::from_secs(5), app_1.join())
+        .await
+        .expect("server should stop");
+}
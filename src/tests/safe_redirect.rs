@@ -0,0 +1,147 @@
+use std::{collections::HashMap, time::Duration};
+
+use axum::{extract::Query, http::StatusCode, routing::post, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthLayer, AuthLoginRedirectResponse, AuthLogoutResponse,
+        FnAuthHandler, RenewalDecision, SafeRedirect,
+    },
+};
+
+const ALLOWED_PREFIXES: &[&str] = &["/app", "/docs/"];
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+fn routes() -> Router {
+    Router::new()
+        .route("/login", post(api_login))
+        .route("/logout", post(api_logout))
+        .route_layer(AuthLayer::new(auth_handler()))
+}
+
+fn safe_redirect(params: &HashMap<String, String>) -> SafeRedirect {
+    params
+        .get("next")
+        .and_then(|next| SafeRedirect::new(next, ALLOWED_PREFIXES))
+        .unwrap_or_else(|| SafeRedirect::new("/app", ALLOWED_PREFIXES).unwrap())
+}
+
+async fn api_login(Query(params): Query<HashMap<String, String>>) -> AuthLoginRedirectResponse {
+    AuthLoginRedirectResponse::with_safe_redirect(
+        AccessTokenResponse::with_time_delta(
+            AccessToken::from("valid"),
+            Duration::from_secs(60),
+            None,
+        ),
+        safe_redirect(&params),
+    )
+}
+
+async fn api_logout(
+    Query(params): Query<HashMap<String, String>>,
+) -> (AuthLogoutResponse, SafeRedirect) {
+    (
+        AuthLogoutResponse::new(Some("/"), Some("/")),
+        safe_redirect(&params),
+    )
+}
+
+#[test]
+fn accept_allowed_local_paths() {
+    for next in [
+        "/app",
+        "/app/",
+        "/app/settings",
+        "/app?tab=1",
+        "/app/settings#profile",
+        "/docs/",
+        "/docs/intro",
+    ] {
+        assert_eq!(
+            SafeRedirect::validate(next, ALLOWED_PREFIXES).as_deref(),
+            Some(next),
+            "{next}"
+        );
+    }
+}
+
+#[test]
+fn reject_unsafe_targets() {
+    for next in [
+        "https://evil.com/app",
+        "//evil.com/app",
+        "/\\evil.com/app",
+        "evil.com/app",
+        "app",
+        "",
+        "/application",
+        "/docs",
+        "/admin",
+        "/app/../admin",
+        "/app/..\\admin",
+        "/app/%2e%2e/admin",
+        "/app/./settings",
+        "/\t/evil.com",
+        "/app\n/settings",
+    ] {
+        assert_eq!(
+            SafeRedirect::validate(next, ALLOWED_PREFIXES),
+            None,
+            "{next:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn login_redirects_to_validated_target() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.post("/login?next=/app/settings").await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    response.assert_header("location", "/app/settings");
+    assert_eq!(response.cookie("access_token").value(), "valid");
+
+    let response = server.post("/login?next=//evil.com").await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    response.assert_header("location", "/app");
+}
+
+#[tokio::test]
+async fn logout_redirects_to_validated_target() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/logout?next=/docs/intro")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    response.assert_header("location", "/docs/intro");
+    assert_eq!(response.cookie("access_token").value(), "");
+
+    let response = server
+        .post("/logout?next=https://evil.com")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_header("location", "/app");
+}
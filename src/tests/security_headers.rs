@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use axum::{
+    http::{header::X_FRAME_OPTIONS, HeaderName, HeaderValue},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    security_headers::{FrameOptions, SecurityHeadersLayer},
+};
+
+fn routes(security_headers_layer: SecurityHeadersLayer) -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route("/embeddable", get(get_embeddable))
+        .route_layer(security_headers_layer)
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+async fn get_embeddable() -> ([(HeaderName, &'static str); 1], &'static str) {
+    ([(X_FRAME_OPTIONS, "SAMEORIGIN")], "embeddable")
+}
+
+#[tokio::test]
+async fn default_headers() {
+    let app = AxumApp::new(routes(SecurityHeadersLayer::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    response.assert_text("index");
+    response.assert_header("x-content-type-options", "nosniff");
+    response.assert_header("x-frame-options", "DENY");
+    response.assert_header("referrer-policy", "strict-origin-when-cross-origin");
+    assert!(!response.headers().contains_key("strict-transport-security"));
+}
+
+#[tokio::test]
+async fn handler_header_not_overwritten() {
+    let app = AxumApp::new(routes(SecurityHeadersLayer::new()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/embeddable").await;
+    response.assert_header("x-frame-options", "SAMEORIGIN");
+    response.assert_header("x-content-type-options", "nosniff");
+}
+
+#[tokio::test]
+async fn configured_headers() {
+    let app = AxumApp::new(routes(
+        SecurityHeadersLayer::new()
+            .content_type_options(false)
+            .frame_options(Some(FrameOptions::SameOrigin))
+            .referrer_policy(None)
+            .hsts(Duration::from_secs(31536000), true),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    assert!(!response.headers().contains_key("x-content-type-options"));
+    response.assert_header("x-frame-options", "SAMEORIGIN");
+    assert!(!response.headers().contains_key("referrer-policy"));
+    assert_eq!(
+        response.header("strict-transport-security"),
+        HeaderValue::from_static("max-age=31536000; includeSubDomains")
+    );
+}
@@ -0,0 +1,107 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, AuthLogoutResponse, FnAuthHandler, RenewalDecision, SessionControl},
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler(access_token_revoked: Arc<AtomicBool>) -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                if access_token.as_str() == "valid" {
+                    Ok(LoginInfo)
+                } else {
+                    Err(StatusCode::UNAUTHORIZED)
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        move |_access_token, _login_info| {
+            let access_token_revoked = access_token_revoked.clone();
+            Box::pin(async move { access_token_revoked.store(true, Ordering::SeqCst) })
+        },
+    )
+}
+
+fn routes(auth_handler: FnAuthHandler<LoginInfo>) -> Router {
+    Router::new()
+        .route("/api/close-account", post(api_close_account))
+        .route("/api/profile", get(get_profile))
+        .route("/api/logout", post(api_logout))
+        .route_layer(AuthLayer::new(auth_handler))
+}
+
+async fn api_close_account(session_control: SessionControl) -> Json<serde_json::Value> {
+    session_control.logout();
+    Json(json!({ "closed": session_control.is_logout_requested() }))
+}
+
+async fn get_profile(session_control: SessionControl) -> Json<serde_json::Value> {
+    Json(json!({ "closed": session_control.is_logout_requested() }))
+}
+
+async fn api_logout(session_control: SessionControl) -> AuthLogoutResponse {
+    session_control.logout_with(AuthLogoutResponse::new(Some("/requested"), Some("/")));
+    AuthLogoutResponse::new(Some("/returned"), Some("/"))
+}
+
+#[tokio::test]
+async fn logout_through_session_control() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(auth_handler(access_token_revoked.clone())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/close-account")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    response.assert_json(&json!({ "closed": true }));
+    assert_eq!(response.cookie("access_token").value(), "");
+    assert!(access_token_revoked.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn no_logout_without_request() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(auth_handler(access_token_revoked.clone())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/api/profile")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    response.assert_json(&json!({ "closed": false }));
+    assert!(response.maybe_cookie("access_token").is_none());
+    assert!(!access_token_revoked.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn returned_logout_response_takes_precedence() {
+    let access_token_revoked = Arc::new(AtomicBool::new(false));
+    let app = AxumApp::new(routes(auth_handler(access_token_revoked.clone())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .post("/api/logout")
+        .add_header("cookie", "access_token=valid")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.cookie("access_token").path(), Some("/returned"));
+    assert!(access_token_revoked.load(Ordering::SeqCst));
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+
+use crate::auth::{AccessToken, FnAuthHandler, RenewalDecision};
+
+pub(super) const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+/// Auth handler of the tests that do not need a token store: access tokens are verified by
+/// `verify_access_token_fn`, valid access tokens are kept, nothing is revoked and every refresh
+/// token is valid.
+pub(super) fn stub_auth_handler<LoginInfoType: Send + Sync + 'static>(
+    verify_access_token_fn: fn(&AccessToken) -> Result<LoginInfoType, StatusCode>,
+) -> FnAuthHandler<LoginInfoType> {
+    stub_auth_handler_with_renewal(verify_access_token_fn, |_access_token| {
+        RenewalDecision::Keep
+    })
+}
+
+/// Same as `stub_auth_handler`, but valid access tokens are renewed for
+/// `ACCESS_TOKEN_EXPIRATION_TIME_DURATION` on every request.
+pub(super) fn renewing_stub_auth_handler<LoginInfoType: Send + Sync + 'static>(
+    verify_access_token_fn: fn(&AccessToken) -> Result<LoginInfoType, StatusCode>,
+) -> FnAuthHandler<LoginInfoType> {
+    stub_auth_handler_with_renewal(verify_access_token_fn, |access_token| {
+        RenewalDecision::Renew(access_token.clone(), ACCESS_TOKEN_EXPIRATION_TIME_DURATION)
+    })
+}
+
+fn stub_auth_handler_with_renewal<LoginInfoType: Send + Sync + 'static>(
+    verify_access_token_fn: fn(&AccessToken) -> Result<LoginInfoType, StatusCode>,
+    renewal_decision_fn: fn(&AccessToken) -> RenewalDecision,
+) -> FnAuthHandler<LoginInfoType> {
+    FnAuthHandler::new(
+        move |access_token| Box::pin(async move { verify_access_token_fn(&access_token) }),
+        move |access_token, _login_info| {
+            Box::pin(async move { renewal_decision_fn(&access_token) })
+        },
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+    .with_refresh_token_fns(
+        |_refresh_token| Box::pin(async { Ok(()) }),
+        |_refresh_token| Box::pin(async {}),
+    )
+}
@@ -0,0 +1,105 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, RenewalDecision, TenantExtractor},
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+    tenant_id: u32,
+}
+
+struct Tenant(u32);
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                match access_token.as_str() {
+                    "token-of-alice" => Ok(LoginInfo {
+                        loginname: "alice".into(),
+                        tenant_id: 1,
+                    }),
+                    "token-of-bob" => Ok(LoginInfo {
+                        loginname: "bob".into(),
+                        tenant_id: 2,
+                    }),
+                    _ => Err(StatusCode::UNAUTHORIZED),
+                }
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+async fn get_tenant(TenantExtractor(tenant): TenantExtractor<Tenant>) -> String {
+    format!("tenant-{}", tenant.0)
+}
+
+#[tokio::test]
+async fn tenant_extractor() {
+    let projection_count = Arc::new(AtomicUsize::new(0));
+    let auth_layer = AuthLayer::new(auth_handler()).with_tenant({
+        let projection_count = projection_count.clone();
+        move |login_info: &LoginInfo| {
+            projection_count.fetch_add(1, Ordering::SeqCst);
+            log::info!("Projecting the tenant of '{}'", login_info.loginname);
+            Tenant(login_info.tenant_id)
+        }
+    });
+    let app = AxumApp::new(
+        Router::new()
+            .route("/tenant", get(get_tenant))
+            .route_layer(auth_layer),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=token-of-alice")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("tenant-1");
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=token-of-bob")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("tenant-2");
+    assert_eq!(projection_count.load(Ordering::SeqCst), 2);
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=unknown")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server.get("/tenant").await;
+    response.assert_status_unauthorized();
+    assert_eq!(projection_count.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn tenant_extractor_without_projection() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/tenant", get(get_tenant))
+            .route_layer(AuthLayer::new(auth_handler())),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/tenant")
+        .add_header("cookie", "access_token=token-of-alice")
+        .await;
+    response.assert_status_unauthorized();
+}
@@ -0,0 +1,119 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{http::StatusCode, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthHandler, AuthLayer, LoginInfoExtractor, RefreshToken, RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct AppState {
+    issued_at_cutoff: Arc<AtomicU64>,
+}
+
+impl AppState {
+    fn new() -> Self {
+        Self {
+            issued_at_cutoff: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        access_token
+            .strip_prefix("issued-at-")
+            .and_then(|issued_at| issued_at.parse().ok())
+            .map(|issued_at| LoginInfo { issued_at })
+            .ok_or(StatusCode::UNAUTHORIZED)
+    }
+
+    fn is_token_acceptable(&self, login_info: &LoginInfo) -> bool {
+        login_info.issued_at >= self.issued_at_cutoff.load(Ordering::SeqCst)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo {
+    issued_at: u64,
+}
+
+fn routes(state: AppState) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(
+            AuthLayer::new(state.clone()).with_verification_cache(16, Duration::from_secs(60)),
+        )
+        .with_state(state)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn reject_tokens_issued_before_cutoff() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone()));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=issued-at-1")
+        .await;
+    response.assert_status_ok();
+
+    state.issued_at_cutoff.store(2, Ordering::SeqCst);
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=issued-at-1")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=issued-at-2")
+        .await;
+    response.assert_status_ok();
+}
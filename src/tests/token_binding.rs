@@ -0,0 +1,162 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    http::{header::USER_AGENT, request::Parts, StatusCode},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AuthHandler, AuthLayer, LoginInfoExtractor, RefreshToken, RenewalDecision,
+    },
+};
+
+#[derive(Clone)]
+struct AppState {
+    verification_count: Arc<AtomicUsize>,
+}
+
+impl AppState {
+    fn new() -> Self {
+        Self {
+            verification_count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        self.verification_count.fetch_add(1, Ordering::SeqCst);
+
+        let bound_user_agent = access_token
+            .strip_prefix("bound-to-")
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        Ok(LoginInfo {
+            bound_user_agent: bound_user_agent.to_string(),
+        })
+    }
+
+    async fn check_access_token_parts(
+        &mut self,
+        _access_token: &AccessToken,
+        login_info: &LoginInfo,
+        parts: &Parts,
+    ) -> Result<(), StatusCode> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok());
+
+        if user_agent == Some(login_info.bound_user_agent.as_str()) {
+            Ok(())
+        } else {
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {}
+}
+
+#[derive(Clone)]
+struct LoginInfo {
+    bound_user_agent: String,
+}
+
+fn routes(auth_layer: AuthLayer<LoginInfo, AppState>) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(auth_layer)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[tokio::test]
+async fn access_token_bound_to_user_agent() {
+    let app = AxumApp::new(routes(AuthLayer::new(AppState::new())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=bound-to-browser")
+        .add_header("user-agent", "browser")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=bound-to-browser")
+        .add_header("user-agent", "another-browser")
+        .await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn cached_access_token_stays_bound_to_user_agent() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(
+        AuthLayer::new(state.clone()).with_verification_cache(16, Duration::from_secs(60)),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=bound-to-browser")
+        .add_header("user-agent", "browser")
+        .await;
+    response.assert_status_ok();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=bound-to-browser")
+        .add_header("user-agent", "another-browser")
+        .await;
+    response.assert_status_unauthorized();
+    assert_eq!(state.verification_count.load(Ordering::SeqCst), 1);
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=bound-to-browser")
+        .add_header("user-agent", "browser")
+        .await;
+    response.assert_status_ok();
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use crate::auth::{AccessToken, AccessTokenResponse, RefreshToken};
+
+#[test]
+fn access_token_from_str() {
+    let expected = AccessToken::new("access-token".to_string());
+
+    assert_eq!(AccessToken::from("access-token"), expected);
+    assert_eq!(AccessToken::from("access-token".to_string()), expected);
+    assert_eq!("access-token".parse::<AccessToken>().unwrap(), expected);
+}
+
+#[test]
+fn refresh_token_from_str() {
+    let expected = RefreshToken::new("refresh-token".to_string());
+
+    assert_eq!(RefreshToken::from("refresh-token"), expected);
+    assert_eq!(RefreshToken::from("refresh-token".to_string()), expected);
+    assert_eq!("refresh-token".parse::<RefreshToken>().unwrap(), expected);
+}
+
+#[test]
+fn access_token_response_from_str() {
+    let access_token_response =
+        AccessTokenResponse::with_time_delta("access-token", Duration::from_secs(60), None);
+
+    assert_eq!(access_token_response.token().as_str(), "access-token");
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+use crate::auth::{AccessToken, AccessTokenResponse, RefreshToken, RefreshTokenResponse};
+
+#[test]
+fn access_token_expires_in() {
+    let access_token_response = AccessTokenResponse::with_time_delta(
+        AccessToken::new("access-token".into()),
+        Duration::from_secs(3600),
+        None,
+    );
+
+    let expires_in = access_token_response.expires_in();
+    assert!(expires_in <= Duration::from_secs(3600));
+    assert!(expires_in > Duration::from_secs(3590));
+}
+
+#[test]
+fn refresh_token_expires_in() {
+    let refresh_token_response = RefreshTokenResponse::with_time_delta(
+        RefreshToken::new("refresh-token".into()),
+        Duration::from_secs(3600),
+        "/",
+    );
+
+    let expires_in = refresh_token_response.expires_in();
+    assert!(expires_in <= Duration::from_secs(3600));
+    assert!(expires_in > Duration::from_secs(3590));
+}
+
+#[test]
+fn expired_token_expires_in_zero() {
+    let access_token_response = AccessTokenResponse::with_offset_date_time(
+        AccessToken::new("access-token".into()),
+        OffsetDateTime::now_utc() - Duration::from_secs(60),
+        None,
+    );
+
+    assert_eq!(access_token_response.expires_in(), Duration::ZERO);
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use crate::auth::{
+    AccessTokenResponse, RefreshTokenResponse, SecureRandomTokenGenerator, TokenGenerator,
+    UuidTokenGenerator,
+};
+
+fn is_url_safe_base64(token: &str) -> bool {
+    token
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[test]
+fn uuid_token() {
+    let token = UuidTokenGenerator.generate_token();
+
+    assert_eq!(token.len(), 36);
+    assert_ne!(token, UuidTokenGenerator.generate_token());
+}
+
+#[test]
+fn secure_random_token() {
+    let token_generator = SecureRandomTokenGenerator::default();
+    let token = token_generator.generate_token();
+
+    assert_eq!(token.len(), 43);
+    assert!(is_url_safe_base64(&token));
+    assert_ne!(token, token_generator.generate_token());
+}
+
+#[test]
+fn secure_random_token_with_entropy() {
+    assert_eq!(SecureRandomTokenGenerator::new(1).generate_token().len(), 2);
+    assert_eq!(SecureRandomTokenGenerator::new(2).generate_token().len(), 3);
+    assert_eq!(SecureRandomTokenGenerator::new(3).generate_token().len(), 4);
+    assert_eq!(
+        SecureRandomTokenGenerator::new(64).generate_token().len(),
+        86
+    );
+}
+
+#[test]
+fn generate_token_responses() {
+    let access_token_response = AccessTokenResponse::generate(Duration::from_secs(60), None);
+    assert_eq!(access_token_response.token().len(), 43);
+    assert!(is_url_safe_base64(access_token_response.token()));
+
+    let refresh_token_response = RefreshTokenResponse::generate(Duration::from_secs(60), "/");
+    assert_eq!(refresh_token_response.token().len(), 43);
+    assert!(is_url_safe_base64(refresh_token_response.token()));
+}
@@ -0,0 +1,124 @@
+use axum::{
+    http::{HeaderName, StatusCode},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, LoginInfoExtractor, TokenSource},
+    tests::support::renewing_stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    source: String,
+}
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    renewing_stub_auth_handler(|access_token| {
+        access_token
+            .strip_prefix("valid-")
+            .map(|source| LoginInfo {
+                source: source.to_string(),
+            })
+            .ok_or(StatusCode::UNAUTHORIZED)
+    })
+}
+
+fn routes() -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route_layer(AuthLayer::new(auth_handler()).with_token_sources([
+            TokenSource::Cookie,
+            TokenSource::BearerHeader,
+            TokenSource::Header(HeaderName::from_static("x-api-key")),
+            TokenSource::Query("access_token".into()),
+        ]))
+}
+
+async fn get_private(LoginInfoExtractor(login_info): LoginInfoExtractor<LoginInfo>) -> String {
+    login_info.source.clone()
+}
+
+#[tokio::test]
+async fn access_token_from_every_source() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid-cookie")
+        .await;
+    response.assert_text("cookie");
+    assert!(response.maybe_cookie("access_token").is_some());
+
+    let response = server
+        .get("/private")
+        .add_header("authorization", "Bearer valid-bearer")
+        .await;
+    response.assert_text("bearer");
+    assert!(response.maybe_cookie("access_token").is_none());
+
+    let response = server
+        .get("/private")
+        .add_header("x-api-key", "valid-header")
+        .await;
+    response.assert_text("header");
+    assert!(response.maybe_cookie("access_token").is_none());
+
+    let response = server
+        .get("/private")
+        .add_query_param("access_token", "valid-query")
+        .await;
+    response.assert_text("query");
+    assert!(response.maybe_cookie("access_token").is_none());
+
+    let response = server.get("/private").await;
+    response.assert_status_unauthorized();
+}
+
+#[tokio::test]
+async fn token_sources_tried_in_order() {
+    let app = AxumApp::new(routes());
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid-cookie")
+        .add_header("authorization", "Bearer valid-bearer")
+        .await;
+    response.assert_text("cookie");
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=invalid-cookie")
+        .add_header("authorization", "Bearer valid-bearer")
+        .await;
+    response.assert_text("bearer");
+    assert!(response.maybe_cookie("access_token").is_none());
+}
+
+#[tokio::test]
+async fn disabled_token_source_is_ignored() {
+    let app = AxumApp::new(
+        Router::new()
+            .route("/private", get(get_private))
+            .route_layer(
+                AuthLayer::new(auth_handler()).with_token_sources([TokenSource::BearerHeader]),
+            ),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=valid-cookie")
+        .await;
+    response.assert_status_unauthorized();
+
+    let response = server
+        .get("/private")
+        .add_header("authorization", "bearer valid-bearer")
+        .await;
+    response.assert_text("bearer");
+}
@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{http::StatusCode, routing::get, Router};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler, RenewalDecision},
+};
+
+#[derive(Clone)]
+struct LoginInfo {
+    loginname: String,
+}
+
+#[derive(Clone, Default)]
+struct RecordedUsers(Arc<Mutex<Vec<String>>>);
+
+struct UserVisitor<'a>(&'a mut Option<String>);
+
+impl Visit for UserVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "user" {
+            *self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecordedUsers {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        let mut user = None;
+        attrs.record(&mut UserVisitor(&mut user));
+        if let Some(user) = user {
+            self.0.lock().unwrap().push(user);
+        }
+    }
+}
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    FnAuthHandler::new(
+        |access_token| {
+            Box::pin(async move {
+                access_token
+                    .strip_prefix("token-of-")
+                    .map(|loginname| LoginInfo {
+                        loginname: loginname.to_string(),
+                    })
+                    .ok_or(StatusCode::UNAUTHORIZED)
+            })
+        },
+        |_access_token, _login_info| Box::pin(async { RenewalDecision::Keep }),
+        |_access_token, _login_info| Box::pin(async {}),
+    )
+}
+
+async fn get_span_name() -> String {
+    tracing::Span::current()
+        .metadata()
+        .map(|metadata| metadata.name())
+        .unwrap_or("none")
+        .to_string()
+}
+
+#[tokio::test]
+async fn tracing_user_field() {
+    let recorded_users = RecordedUsers::default();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::registry().with(recorded_users.clone()),
+    );
+
+    let app = AxumApp::new(
+        Router::new()
+            .route("/private", get(get_span_name))
+            .route_layer(
+                AuthLayer::new(auth_handler())
+                    .with_tracing_user(|login_info: &LoginInfo| login_info.loginname.clone()),
+            ),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("auth");
+    assert_eq!(*recorded_users.0.lock().unwrap(), vec!["user".to_string()]);
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+    response.assert_text("none");
+    assert_eq!(recorded_users.0.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn tracing_user_field_disabled_by_default() {
+    let recorded_users = RecordedUsers::default();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::registry().with(recorded_users.clone()),
+    );
+
+    let app = AxumApp::new(
+        Router::new()
+            .route("/private", get(get_span_name))
+            .route_layer(AuthLayer::new(auth_handler())),
+    );
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", "access_token=token-of-user")
+        .await;
+    response.assert_status_ok();
+    response.assert_text("none");
+    assert!(recorded_users.0.lock().unwrap().is_empty());
+}
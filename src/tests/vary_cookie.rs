@@ -0,0 +1,79 @@
+use axum::{http::header::VARY, routing::get, Router};
+
+use crate::{
+    app::AxumApp,
+    auth::{AuthLayer, FnAuthHandler},
+    tests::support::stub_auth_handler,
+};
+
+#[derive(Clone)]
+struct LoginInfo;
+
+fn auth_handler() -> FnAuthHandler<LoginInfo> {
+    stub_auth_handler(|_access_token| Ok(LoginInfo))
+}
+
+fn routes(auth_layer: AuthLayer<LoginInfo, FnAuthHandler<LoginInfo>>) -> Router {
+    Router::new()
+        .route("/", get(get_index))
+        .route("/vary-accept", get(get_vary_accept))
+        .route_layer(auth_layer)
+}
+
+async fn get_index() -> &'static str {
+    "index"
+}
+
+async fn get_vary_accept() -> ([(&'static str, &'static str); 1], &'static str) {
+    ([("vary", "Accept")], "vary-accept")
+}
+
+fn vary_header_values(response: &axum_test::TestResponse) -> Vec<String> {
+    response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .map(|header_value| header_value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn vary_cookie_on_authenticated_response() {
+    let app = AxumApp::new(routes(AuthLayer::new(auth_handler())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    assert_eq!(vary_header_values(&response), vec!["Cookie"]);
+
+    let response = server
+        .get("/vary-accept")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    assert_eq!(vary_header_values(&response), vec!["Accept", "Cookie"]);
+}
+
+#[tokio::test]
+async fn no_vary_cookie_without_access_token() {
+    let app = AxumApp::new(routes(AuthLayer::new(auth_handler())));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server.get("/").await;
+    assert!(vary_header_values(&response).is_empty());
+}
+
+#[tokio::test]
+async fn vary_cookie_disabled() {
+    let app = AxumApp::new(routes(
+        AuthLayer::new(auth_handler()).with_vary_cookie(false),
+    ));
+    let server = app.spawn_test_server().unwrap();
+
+    let response = server
+        .get("/")
+        .add_header("cookie", "access_token=access-token")
+        .await;
+    assert!(vary_header_values(&response).is_empty());
+}
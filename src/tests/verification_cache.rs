@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
+
+use crate::{
+    app::AxumApp,
+    auth::{
+        AccessToken, AccessTokenResponse, AuthHandler, AuthLayer, AuthLogoutResponse,
+        LoginInfoExtractor, RefreshToken, RenewalDecision,
+    },
+};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+const ACCESS_TOKEN_EXPIRATION_TIME_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct AppState {
+    logins: Arc<Mutex<BTreeMap<AccessToken, LoginInfo>>>,
+    verification_count: Arc<AtomicUsize>,
+}
+
+impl AppState {
+    fn new() -> Self {
+        Self {
+            logins: Arc::new(Mutex::new(BTreeMap::new())),
+            verification_count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthHandler<LoginInfo> for AppState {
+    async fn verify_access_token(
+        &mut self,
+        access_token: &AccessToken,
+    ) -> Result<LoginInfo, StatusCode> {
+        self.verification_count.fetch_add(1, Ordering::SeqCst);
+
+        self.logins
+            .lock()
+            .get(access_token)
+            .cloned()
+            .ok_or(StatusCode::BAD_REQUEST)
+    }
+
+    async fn update_access_token(
+        &mut self,
+        _access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) -> RenewalDecision {
+        RenewalDecision::Keep
+    }
+
+    async fn revoke_access_token(
+        &mut self,
+        access_token: &AccessToken,
+        _login_info: &Arc<LoginInfo>,
+    ) {
+        self.logins.lock().remove(access_token);
+    }
+
+    async fn verify_refresh_token(
+        &mut self,
+        _refresh_token: &RefreshToken,
+    ) -> Result<(), StatusCode> {
+        unreachable!("tests contained in this file, this line should not be called")
+    }
+
+    async fn revoke_refresh_token(&mut self, _refresh_token: &RefreshToken) {
+        unreachable!("tests contained in this file, this line should not be called")
+    }
+}
+
+fn routes(state: AppState, ttl: Duration) -> Router {
+    Router::new()
+        .route("/private", get(get_private))
+        .route("/api/login", post(api_login))
+        .route("/api/logout", post(api_logout))
+        .route_layer(AuthLayer::new(state.clone()).with_verification_cache(16, ttl))
+        .with_state(state)
+}
+
+async fn get_private(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> &'static str {
+    "private"
+}
+
+#[derive(Clone)]
+struct LoginInfo;
+
+async fn api_login(State(state): State<AppState>) -> AccessTokenResponse {
+    let access_token_response = AccessTokenResponse::with_time_delta(
+        AccessToken::new(Uuid::new_v4().as_hyphenated().to_string()),
+        ACCESS_TOKEN_EXPIRATION_TIME_DURATION,
+        None,
+    );
+
+    state
+        .logins
+        .lock()
+        .insert(access_token_response.token().clone(), LoginInfo);
+
+    access_token_response
+}
+
+async fn api_logout(
+    LoginInfoExtractor(_login_info): LoginInfoExtractor<LoginInfo>,
+) -> AuthLogoutResponse {
+    AuthLogoutResponse::new(Some("/"), Some("/"))
+}
+
+#[tokio::test]
+async fn cached_verification() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone(), Duration::from_secs(60)));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login").await;
+
+    for _ in 0..3 {
+        let response = server.get("/private").await;
+        response.assert_status_ok();
+    }
+
+    assert_eq!(state.verification_count.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn expired_cache_entry() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone(), Duration::from_millis(100)));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    server.post("/api/login").await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let response = server.get("/private").await;
+    response.assert_status_ok();
+
+    assert_eq!(state.verification_count.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn logout_evicts_cache_entry() {
+    let state = AppState::new();
+    let app = AxumApp::new(routes(state.clone(), Duration::from_secs(60)));
+    let mut server = app.spawn_test_server().unwrap();
+    server.do_save_cookies();
+
+    let access_token = server.post("/api/login").await.cookie("access_token");
+
+    server.post("/api/logout").await;
+
+    let response = server
+        .get("/private")
+        .add_header("cookie", format!("access_token={}", access_token.value()))
+        .await;
+    response.assert_status_bad_request();
+}